@@ -0,0 +1,398 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::errors::{ErrorKind, FormatError, UnallowedCharacterReason};
+use crate::WHITESPACE_TAB;
+
+/// a stable identifier for every kind of error, written like `T0024`, which stays the same across releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ErrorCode(u16);
+
+impl ErrorCode {
+    /// the longer explanation of the error along with examples
+    pub fn explain(self) -> &'static Explanation {
+        CATALOGUE.iter()
+            .find(|explanation| explanation.code == self)
+            .expect("every code has an explanation")
+    }
+
+    /// every known code, in order
+    pub fn all() -> impl Iterator<Item = ErrorCode> {
+        CATALOGUE.iter().map(|explanation| explanation.code)
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "T{:04}", self.0)
+    }
+}
+
+/// `code` is not written like `T0024` or is not a known code
+#[derive(Debug)]
+pub struct UnknownCode(std::string::String);
+
+impl Display for UnknownCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown error code `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownCode {}
+
+impl FromStr for ErrorCode {
+    type Err = UnknownCode;
+
+    /// the leading `T` can be written in either case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix(['T', 't'])
+            .filter(|digits| digits.len() == 4 && digits.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|digits| digits.parse().ok())
+            .map(ErrorCode)
+            .filter(|code| CATALOGUE.iter().any(|explanation| explanation.code == *code))
+            .ok_or_else(|| UnknownCode(s.to_string()))
+    }
+}
+
+impl UnallowedCharacterReason {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode(match self {
+            UnallowedCharacterReason::InComment => 1,
+            UnallowedCharacterReason::InTypeNumber => 2,
+            UnallowedCharacterReason::InTypeRadixInteger => 3,
+            UnallowedCharacterReason::InTypeBoolean => 4,
+            UnallowedCharacterReason::InTypeBasicString => 5,
+            UnallowedCharacterReason::InTypeMultilineBasicString => 6,
+            UnallowedCharacterReason::InTypeMultilineLiteralString => 7,
+            UnallowedCharacterReason::InTypeLiteralString => 8,
+            UnallowedCharacterReason::InUnicodeSequence => 9,
+            UnallowedCharacterReason::InTypeDate => 10,
+            UnallowedCharacterReason::InTypeTime => 11,
+            UnallowedCharacterReason::InTypeDateTime => 12,
+            UnallowedCharacterReason::InKey => 13,
+            UnallowedCharacterReason::InInlineTable => 14,
+        })
+    }
+}
+
+impl FormatError {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode(match self {
+            FormatError::UnallowedCharacter(_, reason) => return reason.code(),
+            FormatError::MalformedBoolean(_) => 4,
+            FormatError::ExpectedCharacter(_) => 15,
+            FormatError::ExpectedSequence(_) => 16,
+            FormatError::UnknownEscapeSequence => 17,
+            FormatError::MisplacedUnderscore(_, _) => 18,
+            FormatError::MalformedNumber(_, _) => 19,
+            FormatError::IntegerOverflow(_) => 20,
+            FormatError::InvalidDateTime(_) => 21,
+            FormatError::EmptyValue => 22,
+            FormatError::UnexpectedEnd => 23,
+            FormatError::DuplicateKey(_, _, _) => 24,
+            FormatError::DuplicateTable(_, _, _) => 25,
+            FormatError::ExtendedInlineTable(_, _, _) => 26,
+            FormatError::ExtendedTableWithDottedKeys(_, _, _) => 27,
+            FormatError::ExtendedStaticArray(_, _, _) => 28,
+            FormatError::Unknown(_) => 29,
+        })
+    }
+}
+
+impl ErrorKind {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ErrorKind::Format(err) => err.code(),
+            ErrorKind::Data(_) => ErrorCode(30),
+            ErrorKind::Other(_) => ErrorCode(31),
+            ErrorKind::Read(_) => ErrorCode(32),
+        }
+    }
+}
+
+/// what an error code stands for, as shown by `toml-parser explain`
+#[derive(Debug)]
+pub struct Explanation {
+    pub code: ErrorCode,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// a document causing the error, `None` for errors the parser does not raise at the moment
+    pub invalid: Option<&'static str>,
+    /// the same document with the error fixed
+    pub valid: Option<&'static str>,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {}", self.code, self.title)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.description)?;
+
+        for (heading, example) in [("invalid", self.invalid), ("valid", self.valid)] {
+            let Some(example) = example else {
+                continue;
+            };
+
+            writeln!(f)?;
+            writeln!(f, "{heading}:")?;
+            for line in example.lines() {
+                // control characters would not show up, so they are written like `\u{1}`
+                let line: std::string::String = line.chars()
+                    .map(|c| if c.is_control() && c != WHITESPACE_TAB { c.escape_unicode().to_string() } else { c.to_string() })
+                    .collect();
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const CATALOGUE: &[Explanation] = &[
+    Explanation {
+        code: ErrorCode(1),
+        title: "control character in a comment",
+        description: "Comments run up to the end of the line and may hold any character except control characters, tabs being the only exception.",
+        invalid: Some("answer = 42 # the\u{85}answer"),
+        valid: Some("answer = 42 # the answer"),
+    },
+    Explanation {
+        code: ErrorCode(2),
+        title: "unexpected character in a number",
+        description: "Decimal integers and floats are made of digits, an optional sign, a decimal point, an exponent and underscores between digits. \
+            Anything else, like a unit, has to go into a string or into the key.",
+        invalid: Some("port = 8080x"),
+        valid: Some("port = 8080"),
+    },
+    Explanation {
+        code: ErrorCode(3),
+        title: "unexpected character in a hexadecimal, octal or binary integer",
+        description: "Integers prefixed with `0x`, `0o` or `0b` may only hold digits of their radix, and they cannot be signed.",
+        invalid: Some("mask = 0xFG"),
+        valid: Some("mask = 0xFF"),
+    },
+    Explanation {
+        code: ErrorCode(4),
+        title: "malformed boolean",
+        description: "Booleans are either `true` or `false`, always written in lowercase, \
+            anything else starting with `t` or `f` is taken for a misspelled boolean.",
+        invalid: Some("enabled = ture"),
+        valid: Some("enabled = true"),
+    },
+    Explanation {
+        code: ErrorCode(5),
+        title: "control character in a basic string",
+        description: "Basic strings cannot hold control characters other than tabs. \
+            They can be written with escape sequences like `\\n` or `\\u0001` instead.",
+        invalid: Some("bell = \"ring\u{1}\""),
+        valid: Some("bell = \"ring\\u0001\""),
+    },
+    Explanation {
+        code: ErrorCode(6),
+        title: "control character in a multi-line basic string",
+        description: "Multi-line basic strings can span lines and hold tabs, but no other control characters. \
+            They can be written with escape sequences like `\\u0001` instead.",
+        invalid: Some("bell = \"\"\"ring\u{1}\"\"\""),
+        valid: Some("bell = \"\"\"ring\\u0001\"\"\""),
+    },
+    Explanation {
+        code: ErrorCode(7),
+        title: "control character in a multi-line literal string",
+        description: "Multi-line literal strings can span lines and hold tabs, but no other control characters. \
+            As literal strings know no escape sequences, a basic string has to be used for those.",
+        invalid: Some("bell = '''ring\u{1}'''"),
+        valid: Some("bell = \"\"\"ring\\u0001\"\"\""),
+    },
+    Explanation {
+        code: ErrorCode(8),
+        title: "control character in a literal string",
+        description: "Literal strings cannot hold control characters other than tabs. \
+            As literal strings know no escape sequences, a basic string has to be used for those.",
+        invalid: Some("bell = 'ring\u{1}'"),
+        valid: Some("bell = \"ring\\u0001\""),
+    },
+    Explanation {
+        code: ErrorCode(9),
+        title: "unexpected character in a unicode escape sequence",
+        description: "Unicode escape sequences are written as `\\u` followed by exactly four or `\\U` followed by exactly eight hexadecimal digits.",
+        invalid: Some("smiley = \"\\u26G3\""),
+        valid: Some("smiley = \"\\u263A\""),
+    },
+    Explanation {
+        code: ErrorCode(10),
+        title: "unexpected character in a date",
+        description: "A date can be followed by a time, separated by a `T` or a single space. \
+            Anything else following the date on the same line has to be a comment.",
+        invalid: Some("released = 1979-05-27 morning"),
+        valid: Some("released = 1979-05-27 # morning"),
+    },
+    Explanation {
+        code: ErrorCode(11),
+        title: "unexpected character in a time",
+        description: "A time without a date is a local time and cannot have an offset. \
+            Add a date to make it an offset date-time.",
+        invalid: Some("alarm = 07:32:00+01:00"),
+        valid: Some("alarm = 1979-05-27T07:32:00+01:00"),
+    },
+    Explanation {
+        code: ErrorCode(12),
+        title: "unexpected character in a date-time",
+        description: "Dates start with a year of four digits, times with an hour of two digits. \
+            The parser only reads a value as a date or time once these digits were found, so this error is not raised at the moment. \
+            Digits broken before the first `-` or `:` are reported as a number instead, see T0002.",
+        invalid: None,
+        valid: None,
+    },
+    Explanation {
+        code: ErrorCode(13),
+        title: "unexpected character in a key",
+        description: "Bare keys may only hold ASCII letters, digits, `-` and `_`. \
+            Any other key, like one with spaces, has to be quoted.",
+        invalid: Some("first name = \"Tom\""),
+        valid: Some("\"first name\" = \"Tom\""),
+    },
+    Explanation {
+        code: ErrorCode(14),
+        title: "trailing comma in an inline table",
+        description: "Unlike arrays, inline tables cannot have a comma after their last entry.",
+        invalid: Some("point = { x = 1, y = 2, }"),
+        valid: Some("point = { x = 1, y = 2 }"),
+    },
+    Explanation {
+        code: ErrorCode(15),
+        title: "expected a character",
+        description: "A character required at this point is missing, like the closing quote of a string, \
+            the closing bracket of an array or the `=` between a key and its value.",
+        invalid: Some("name = \"Tom"),
+        valid: Some("name = \"Tom\""),
+    },
+    Explanation {
+        code: ErrorCode(16),
+        title: "expected a sequence of characters",
+        description: "Characters required at this point are missing, like the closing quotes of a multi-line string \
+            or a part of a date or time.",
+        invalid: Some("alarm = 07:32"),
+        valid: Some("alarm = 07:32:00"),
+    },
+    Explanation {
+        code: ErrorCode(17),
+        title: "unknown escape sequence",
+        description: "Basic strings know the escape sequences `\\b`, `\\t`, `\\n`, `\\f`, `\\r`, `\\\"`, `\\\\`, `\\uXXXX` and `\\UXXXXXXXX`. \
+            A backslash meant literally has to be escaped, or the string written as a literal string.",
+        invalid: Some("path = \"C:\\data\""),
+        valid: Some("path = 'C:\\data'"),
+    },
+    Explanation {
+        code: ErrorCode(18),
+        title: "misplaced underscore in a number",
+        description: "Underscores can group the digits of a number, but each one has to sit between two digits.",
+        invalid: Some("population = 8__000_000"),
+        valid: Some("population = 8_000_000"),
+    },
+    Explanation {
+        code: ErrorCode(19),
+        title: "malformed number",
+        description: "Decimal integers cannot have leading zeros, and floats need digits on both sides of the decimal point \
+            as well as in the exponent.",
+        invalid: Some("ratio = 3."),
+        valid: Some("ratio = 3.0"),
+    },
+    Explanation {
+        code: ErrorCode(20),
+        title: "integer out of range",
+        description: "Integers have to fit into 64 bits, larger numbers can be written as floats or strings.",
+        invalid: Some("big = 9223372036854775808"),
+        valid: Some("big = 9223372036854775807"),
+    },
+    Explanation {
+        code: ErrorCode(21),
+        title: "invalid date, time or offset",
+        description: "The date, time or offset is well formed but does not exist, like the 30th of February or the 25th hour.",
+        invalid: Some("released = 1979-02-30"),
+        valid: Some("released = 1979-02-28"),
+    },
+    Explanation {
+        code: ErrorCode(22),
+        title: "empty value",
+        description: "Every key needs a value, there is no value standing for nothing. \
+            Leave the key out or use an empty string or array instead.",
+        invalid: Some("name ="),
+        valid: Some("name = \"\""),
+    },
+    Explanation {
+        code: ErrorCode(23),
+        title: "unexpected end",
+        description: "The line or the document ended before what was being parsed was complete.",
+        invalid: Some("[server"),
+        valid: Some("[server]"),
+    },
+    Explanation {
+        code: ErrorCode(24),
+        title: "key defined twice",
+        description: "A key can only be defined once per table.",
+        invalid: Some("name = \"Tom\"\nname = \"Thomas\""),
+        valid: Some("name = \"Tom\"\nnickname = \"Thomas\""),
+    },
+    Explanation {
+        code: ErrorCode(25),
+        title: "table defined twice",
+        description: "A table can only be defined once, be it by a header or by dotted keys. \
+            Entries of a table have to go below its single header.",
+        invalid: Some("[server]\nhost = \"example.org\"\n[server]\nport = 8080"),
+        valid: Some("[server]\nhost = \"example.org\"\nport = 8080"),
+    },
+    Explanation {
+        code: ErrorCode(26),
+        title: "inline table extended",
+        description: "Inline tables are complete in themselves, neither headers nor dotted keys can add to them.",
+        invalid: Some("point = { x = 1 }\n[point]\ny = 2"),
+        valid: Some("point = { x = 1, y = 2 }"),
+    },
+    Explanation {
+        code: ErrorCode(27),
+        title: "table extended with dotted keys",
+        description: "Dotted keys cannot add to a table that was already defined by a header, \
+            its entries have to go below that header.",
+        invalid: Some("[a.b]\nc = 1\n[a]\nb.d = 2"),
+        valid: Some("[a.b]\nc = 1\nd = 2"),
+    },
+    Explanation {
+        code: ErrorCode(28),
+        title: "array extended as an array of tables",
+        description: "Arrays written as a value are complete in themselves, `[[header]]`s cannot add tables to them.",
+        invalid: Some("points = [{ x = 1 }]\n[[points]]\nx = 2"),
+        valid: Some("[[points]]\nx = 1\n[[points]]\nx = 2"),
+    },
+    Explanation {
+        code: ErrorCode(29),
+        title: "unknown error",
+        description: "The parser failed for a reason it has no better description for. \
+            Every error found by the parser has a code of its own, so this one is not raised at the moment.",
+        invalid: None,
+        valid: None,
+    },
+    Explanation {
+        code: ErrorCode(30),
+        title: "value does not fit its type",
+        description: "The document is valid TOML, but a value does not fit the type it is deserialized into, \
+            or a required key is missing.",
+        invalid: Some("# read into a struct with `port: u16`\nport = \"8080\""),
+        valid: Some("# read into a struct with `port: u16`\nport = 8080"),
+    },
+    Explanation {
+        code: ErrorCode(31),
+        title: "value refused",
+        description: "A number looked well formed while parsing but could not be converted. \
+            The parser checks every literal before converting it, so this error is not raised at the moment.",
+        invalid: None,
+        valid: None,
+    },
+    Explanation {
+        code: ErrorCode(32),
+        title: "input could not be read",
+        description: "Reading the document failed part way, either the reader returned an I/O error or the input holds bytes \
+            that are not UTF-8. TOML documents are always UTF-8 encoded, re-encode the file if it was saved in another encoding.",
+        invalid: None,
+        valid: None,
+    },
+];
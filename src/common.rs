@@ -21,6 +21,9 @@ pub trait CharExt {
     fn is_whitespace(&self) -> bool;
 
     fn is_comment_start(&self) -> bool;
+
+    /// whether the character can close a non-quoted value, be it at the end of a line or inside an array or an inline table
+    fn is_value_end(&self) -> bool;
 }
 
 impl CharExt for char {
@@ -39,6 +42,10 @@ impl CharExt for char {
     fn is_comment_start(&self) -> bool {
         *self == COMMENT_START
     }
+
+    fn is_value_end(&self) -> bool {
+        self.is_linebreak() || self.is_whitespace() || self.is_comment_start() || [',', ']', '}'].contains(self)
+    }
 }
 
 pub fn check_comment_or_whitespaces(input: &mut impl Supplier, is_comment: bool) -> Option<ParserError> {
@@ -70,31 +77,22 @@ pub fn check_comment_or_whitespaces(input: &mut impl Supplier, is_comment: bool)
     None
 }
 
-pub struct Counter {
-    value: u8,
-    max: u8
-}
-
-impl Counter {
-    pub fn new(max: u8) -> Self {
-        Counter {
-            value: 0b0,
-            max
-        }
-    }
+/// skips whitespaces, line breaks and comments starting with the already read `current`, returns the first character of interest
+pub fn skip_blanks(input: &mut impl Supplier, current: Option<char>) -> Result<Option<char>,ParserError> {
+    let mut c = current;
 
-    pub fn inc(mut self) -> Self {
-        if !self.is_capped() {
-            self.value += 0b1;
+    loop {
+        match c {
+            Some(_c) if _c.is_comment_start() => {
+                if let Some(err) = check_comment_or_whitespaces(input, true) {
+                    return ParserError::extend(err);
+                }
+                c = input.get();
+            },
+            Some(_c) if _c.is_whitespace() => {
+                c = input.get();
+            },
+            _ => return Ok(c),
         }
-        self
-    }
-
-    pub fn is_capped(&self) -> bool {
-        self.value >= self.max
     }
-
-    pub fn is_zero(&self) -> bool {
-        self.value == 0b0
-    }
-}
\ No newline at end of file
+}
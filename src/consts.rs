@@ -1,10 +1,9 @@
 pub const COMMENT_START      : char = '\u{0023}';
    
-pub const WHITESPACE_TAB     : char = 0x0A as char;
+pub const WHITESPACE_TAB     : char = 0x09 as char;
 pub const WHITESPACE_SPACE   : char = 0x20 as char;
    
 pub const ESCAPE_START       : char = '\\';
-pub const ESCAPE_START_STR   : &str = r"\";
    
 pub const DOUBLE_QUOTE       : char = '"';
 pub const SINGLE_QUOTE       : char = '\'';
@@ -59,26 +58,32 @@ pub const ESCAPE_SEQUENCE_TO_CHAR: [(&str,char); 7] = [
 
 pub const LINE_ENDING_BACKSLASH: &str = "\\\n";
 
-const fn bare_keys_chars() -> [char; 62] {
-    let mut arr: [char; 62] = ['\0'; 62];
+pub const RADIX_PREFIXES: [(&str, u32); 3] = [
+    ("0x", 16),
+    ("0o", 8),
+    ("0b", 2),
+];
+
+const fn bare_keys_chars() -> [char; 64] {
+    let mut arr: [char; 64] = ['\0'; 64];
     let mut i = 0;
 
-    let mut c = 'a' as u8;
-    while c != 'z' as u8 {
+    let mut c = b'a';
+    while c != b'z' + 1 {
         arr[i] = c as char;
         i += 1;
         c += 1;
     }
 
-    let mut c = 'A' as u8;
-    while c != 'Z' as u8 {
+    let mut c = b'A';
+    while c != b'Z' + 1 {
         arr[i] = c as char;
         i += 1;
         c += 1;
     }
 
-    let mut c = '0' as u8;
-    while c != '9' as u8 {
+    let mut c = b'0';
+    while c != b'9' + 1 {
         arr[i] = c as char;
         i += 1;
         c += 1;
@@ -90,4 +95,4 @@ const fn bare_keys_chars() -> [char; 62] {
     arr
 }
 
-pub const BARE_KEY_CHARS: [char; 62] = bare_keys_chars();
\ No newline at end of file
+pub const BARE_KEY_CHARS: [char; 64] = bare_keys_chars();
\ No newline at end of file
@@ -0,0 +1,272 @@
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::errors::{DataError, ParserError};
+use crate::parsers::DocumentParser;
+use crate::reader::char_supplier::{Reader, Span};
+use crate::types::{Key, NumberType, Segment, Spans, Table, Value};
+
+/// raised while walking the tree in either direction, the path is filled in from the inside out as the error travels up
+#[derive(Debug)]
+pub struct Error {
+    path: Vec<Segment>,
+    message: std::string::String,
+}
+
+impl Error {
+    pub(crate) fn under(mut self, segment: Segment) -> Self {
+        self.path.insert(0, segment);
+        self
+    }
+
+    pub(crate) fn into_data_error(self) -> DataError {
+        DataError {
+            path: Segment::join(&self.path),
+            message: self.message,
+        }
+    }
+
+    /// where the deepest part of the path the document has a location for was written, headers and keys are preferred over values
+    fn locate(&self, spans: &Spans) -> Option<Span> {
+        (0..=self.path.len()).rev()
+            .filter_map(|len| spans.get(&self.path[..len]))
+            .find_map(|location| location.header.or(location.key).or(location.value))
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error {
+            path: Vec::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// reads `T` out of the document `text`, errors name the offending key and point at where it was defined
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, ParserError> {
+    let mut reader = Reader::new(text.as_bytes());
+    let mut supplier = reader.iter_with_debug();
+
+    let (root, spans) = match DocumentParser::parse(&mut supplier) {
+        Ok(parsed) => parsed,
+        Err(err) => return Err(err.located(&mut supplier)),
+    };
+
+    T::deserialize(Deserializer::Table(&root)).map_err(|err| {
+        let span = err.locate(&spans);
+        let path = err.path.clone();
+        let err_at = ParserError::from::<(), _>(err.into_data_error()).unwrap_err().under_path(&path);
+
+        match span {
+            Some(span) => err_at.at(span),
+            None => err_at,
+        }
+    })
+}
+
+/// walks a parsed tree, the root and the elements of arrays of tables are tables without a surrounding value
+#[derive(Clone, Copy)]
+enum Deserializer<'a> {
+    Value(&'a Value),
+    Table(&'a Table),
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let value = match self {
+            Deserializer::Table(table) => return visitor.visit_map(TableAccess::new(table)),
+            Deserializer::Value(value) => value,
+        };
+
+        match value {
+            Value::String(v) => visitor.visit_str(v),
+            Value::Boolean(v) => visitor.visit_bool(*v),
+            Value::Number(NumberType::Integer(v)) => visitor.visit_i64(*v),
+            Value::Number(NumberType::Float(v)) => visitor.visit_f64(*v),
+            #[cfg(feature = "big-integer")]
+            // anything in the range of `i64` is an `Integer`, so the ones up to `u64::MAX` are handed out the way serde expects them for `u64`
+            Value::Number(NumberType::BigInteger(v)) => match (u64::try_from(v), i128::try_from(v), u128::try_from(v)) {
+                (Ok(v), _, _) => visitor.visit_u64(v),
+                (_, Ok(v), _) => visitor.visit_i128(v),
+                (_, _, Ok(v)) => visitor.visit_u128(v),
+                _ => Err(de::Error::custom(format!("integer `{v}` does not fit into 128 bits"))),
+            },
+            // date-times are handed out in their TOML form, which is RFC 3339 for the ones with a date and an offset
+            Value::DateTime(v) => visitor.visit_string(v.to_string()),
+            Value::Array(values) => visitor.visit_seq(ArrayAccess::new(values.iter().map(Deserializer::Value).collect())),
+            Value::ArrayOfTables(tables) => visitor.visit_seq(ArrayAccess::new(tables.iter().map(Deserializer::Table).collect())),
+            Value::Nested(table) => visitor.visit_map(TableAccess::new(table)),
+        }
+    }
+
+    /// a key that is present always holds something, missing keys are left to serde which turns them into `None`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// unit variants are written as strings, other variants as a table holding a single key named after the variant
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        let table = match self {
+            Deserializer::Value(Value::String(variant)) => return visitor.visit_enum(variant.as_str().into_deserializer()),
+            Deserializer::Value(Value::Nested(table)) | Deserializer::Table(table) => table,
+            Deserializer::Value(value) => return Err(de::Error::invalid_type(unexpected(value), &"a string or a table with a single key")),
+        };
+
+        let mut entries = table.iter();
+        match (entries.next(), entries.next()) {
+            (Some((key, value)), None) => visitor.visit_enum(EnumAccess { variant: key, value }),
+            _ => Err(de::Error::invalid_length(table.len(), &"a table with a single key")),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+fn unexpected(value: &Value) -> de::Unexpected<'_> {
+    match value {
+        Value::String(v) => de::Unexpected::Str(v),
+        Value::Boolean(v) => de::Unexpected::Bool(*v),
+        Value::Number(NumberType::Integer(v)) => de::Unexpected::Signed(*v),
+        Value::Number(NumberType::Float(v)) => de::Unexpected::Float(*v),
+        #[cfg(feature = "big-integer")]
+        Value::Number(NumberType::BigInteger(_)) => de::Unexpected::Other("big integer"),
+        Value::DateTime(_) => de::Unexpected::Other("date-time"),
+        Value::Array(_) | Value::ArrayOfTables(_) => de::Unexpected::Seq,
+        Value::Nested(_) => de::Unexpected::Map,
+    }
+}
+
+struct TableAccess<'a> {
+    entries: std::vec::IntoIter<(&'a Key, &'a Value)>,
+    current: Option<(&'a Key, &'a Value)>,
+}
+
+impl<'a> TableAccess<'a> {
+    fn new(table: &'a Table) -> Self {
+        TableAccess {
+            entries: table.iter().collect::<Vec<_>>().into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for TableAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        self.current = self.entries.next();
+
+        match self.current {
+            Some((key, _)) => seed.deserialize(key.name().into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (key, value) = self.current.expect("a value is only asked for after its key");
+
+        seed.deserialize(Deserializer::Value(value))
+            .map_err(|err: Error| err.under(Segment::Key(key.clone())))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct ArrayAccess<'a> {
+    elements: std::iter::Enumerate<std::vec::IntoIter<Deserializer<'a>>>,
+}
+
+impl<'a> ArrayAccess<'a> {
+    fn new(elements: Vec<Deserializer<'a>>) -> Self {
+        ArrayAccess {
+            elements: elements.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        match self.elements.next() {
+            Some((i, element)) => seed.deserialize(element)
+                .map(Some)
+                .map_err(|err: Error| err.under(Segment::Index(i))),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct EnumAccess<'a> {
+    variant: &'a Key,
+    value: &'a Value,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+    type Error = Error;
+    type Variant = VariantAccess<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(self.variant.name().into_deserializer())?;
+        Ok((variant, VariantAccess { variant: self.variant, value: self.value }))
+    }
+}
+
+struct VariantAccess<'a> {
+    variant: &'a Key,
+    value: &'a Value,
+}
+
+impl VariantAccess<'_> {
+    fn wrap(&self, err: Error) -> Error {
+        err.under(Segment::Key(self.variant.clone()))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Err(self.wrap(de::Error::invalid_type(unexpected(self.value), &"a unit variant written as a string")))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+        seed.deserialize(Deserializer::Value(self.value)).map_err(|err| self.wrap(err))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(Deserializer::Value(self.value), visitor).map_err(|err| self.wrap(err))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(Deserializer::Value(self.value), visitor).map_err(|err| self.wrap(err))
+    }
+}
@@ -0,0 +1,646 @@
+use crate::{errors::{FormatError, ParserError}, parsers::{KeyParser, ValueParser}, reader::char_supplier::{Supplier, ToSupplier}, skip_whitespaces, CharExt, types::{Key, Segment, Table, Value}, writer, COMMENT_START, NEWLINE_CR, NEWLINE_CRLF, NEWLINE_LF, NEWLINE_LF_STR, WHITESPACE_SPACE, WHITESPACE_TAB};
+
+/// a piece of the source, everything but the parsed paths is kept exactly as it was written
+#[derive(Clone, Debug)]
+enum Item {
+    /// blank lines and lines holding only a comment
+    Trivia(std::string::String),
+    /// a `[table]` or `[[array.of.tables]]` line
+    Header {
+        path: Vec<Key>,
+        raw: std::string::String,
+        /// the comment after the closing bracket, found while parsing since quoted keys may hold a `#` as well
+        comment: Option<std::string::String>,
+    },
+    /// a `key = value` entry, `path` is the full path including the enclosing header
+    Entry {
+        path: Vec<Key>,
+        /// indentation, key, `=` and whitespace up to the value
+        key: std::string::String,
+        value: std::string::String,
+        /// whatever follows the value up to and including the line break
+        suffix: std::string::String,
+    },
+}
+
+impl Item {
+    fn ends_with_linebreak(&self) -> bool {
+        let raw = match self {
+            Item::Trivia(raw) | Item::Header { raw, .. } => raw,
+            Item::Entry { suffix, .. } => suffix,
+        };
+        raw.ends_with(NEWLINE_LF)
+    }
+
+    fn push_linebreak(&mut self, linebreak: &str) {
+        match self {
+            Item::Trivia(raw) | Item::Header { raw, .. } => raw.push_str(linebreak),
+            Item::Entry { suffix, .. } => suffix.push_str(linebreak),
+        }
+    }
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Item::Trivia(raw) | Item::Header { raw, .. } => write!(f, "{raw}"),
+            Item::Entry { key, value, suffix, .. } => write!(f, "{key}{value}{suffix}"),
+        }
+    }
+}
+
+/// comments attached to a key, a table header or an array element, the text excludes the `#`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Comments {
+    /// comment lines directly above
+    pub leading: Vec<std::string::String>,
+    /// the comment at the end of the line
+    pub trailing: Option<std::string::String>,
+}
+
+/// a document which keeps comments, whitespace, key quoting and the way values were written,
+/// only the parts touched by an edit are rewritten and everything else is written back byte for byte
+///
+/// [`Document::set`] and [`Document::remove`] look keys under an array of tables up in its last element, the same way entries
+/// following a header are routed, comments are looked up by a path with the index of the element like the one of [`crate::Spans`]
+#[derive(Clone, Debug)]
+pub struct Document {
+    items: Vec<Item>,
+    /// the line break the document is written with, used for every line an edit adds
+    linebreak: &'static str,
+}
+
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map_or(text.len(), |(i, _)| i)
+}
+
+fn to_keys(path: &[&str]) -> Vec<Key> {
+    path.iter().map(|name| Key::new(name.to_string())).collect()
+}
+
+/// the text of the comment in `rest`, which holds whatever follows a value or a header on its line
+fn trailing_comment(rest: &str) -> Option<std::string::String> {
+    rest.find(COMMENT_START).map(|start| comment_text(&rest[start + 1..]))
+}
+
+fn comment_text(comment: &str) -> std::string::String {
+    comment.trim_end_matches([NEWLINE_LF, NEWLINE_CR]).to_string()
+}
+
+/// comment lines directly above an item, a blank line in between detaches them
+fn leading_comments(trivia: &str) -> Vec<std::string::String> {
+    let mut comments: Vec<std::string::String> = trivia.lines().rev()
+        .map(|line| line.trim_start_matches([WHITESPACE_SPACE, WHITESPACE_TAB]))
+        .take_while(|line| line.starts_with(COMMENT_START))
+        .map(|line| comment_text(&line[1..]))
+        .collect();
+
+    comments.reverse();
+    comments
+}
+
+/// where the comment lines directly above the next item start in `trivia`, right behind its last blank line
+fn attached_start(trivia: &str) -> usize {
+    let mut start = 0;
+    let mut read = 0;
+
+    for line in trivia.split_inclusive(NEWLINE_LF) {
+        read += line.len();
+        if line.trim_matches([WHITESPACE_SPACE, WHITESPACE_TAB, NEWLINE_CR, NEWLINE_LF]).is_empty() {
+            start = read;
+        }
+    }
+
+    start
+}
+
+/// walks the elements of an array written as `raw`, comments above an element lead it and a comment on the line it ends on trails it,
+/// comments inside a nested array go to the element holding it
+fn element_comments(raw: &str) -> Result<Vec<Comments>, ParserError> {
+    let line_of = |read: usize| raw[..byte_offset(raw, read)].matches(NEWLINE_LF).count();
+
+    let mut input = ToSupplier::from_string(raw);
+    let mut elements: Vec<(Comments, usize)> = Vec::new();
+    let mut pending = Vec::new();
+
+    input.get();
+    let mut c = input.get();
+
+    loop {
+        match c {
+            Some(_c) if CharExt::is_whitespace(&_c) || _c.is_linebreak() || _c == ',' => c = input.get(),
+            Some(_c) if _c.is_comment_start() => {
+                let line = line_of(input.position().column);
+                let mut text = std::string::String::new();
+                while let Some(_c) = input.get().filter(|_c| !_c.is_linebreak()) {
+                    text.push(_c);
+                }
+
+                match elements.last_mut() {
+                    Some((comments, end)) if *end == line && comments.trailing.is_none() => comments.trailing = Some(text),
+                    _ => pending.push(text),
+                }
+                c = input.get();
+            },
+            Some(']') | None => break,
+            Some(first) => {
+                // `first` is already read, a nested array is walked from its opening bracket
+                let start = input.position().column - 1;
+                ValueParser::parse_value(first, &mut input)?;
+
+                let read = input.position().column;
+                let end = if input.last().is_some() { read - 1 } else { read };
+                let inner = &raw[byte_offset(raw, start)..byte_offset(raw, end)];
+
+                let mut comments = Comments { leading: std::mem::take(&mut pending), trailing: None };
+                if first == '[' {
+                    for element in element_comments(inner)? {
+                        comments.leading.extend(element.leading);
+                        comments.leading.extend(element.trailing);
+                    }
+                }
+
+                elements.push((comments, line_of(end)));
+                c = input.last();
+            },
+        }
+    }
+
+    Ok(elements.into_iter().map(|(comments, _)| comments).collect())
+}
+
+/// the path of every header and entry from the root, elements of arrays of tables included, `None` for trivia
+fn segment_paths(items: &[Item]) -> Vec<Option<Vec<Segment>>> {
+    // elements of each array of tables seen so far
+    let mut arrays: std::collections::HashMap<Vec<Segment>, usize> = std::collections::HashMap::new();
+    let mut current = Vec::new();
+
+    let resolve = |keys: &[Key], arrays: &std::collections::HashMap<Vec<Segment>, usize>| {
+        let mut path = Vec::new();
+        for key in keys {
+            path.push(Segment::Key(key.clone()));
+            if let Some(elements) = arrays.get(&path) {
+                path.push(Segment::Index(elements - 1));
+            }
+        }
+        path
+    };
+
+    items.iter().map(|item| match item {
+        Item::Header { path, raw, .. } => {
+            current = if raw.trim_start().starts_with("[[") {
+                let mut array = resolve(&path[..path.len() - 1], &arrays);
+                array.push(Segment::Key(path[path.len() - 1].clone()));
+
+                let elements = arrays.entry(array.clone()).or_default();
+                array.push(Segment::Index(*elements));
+                *elements += 1;
+                array
+            } else {
+                resolve(path, &arrays)
+            };
+            Some(current.clone())
+        },
+        // the path of an entry includes its header, only the keys of the entry itself are added
+        Item::Entry { path, .. } => {
+            let own = path.len() - current.iter().filter(|segment| matches!(segment, Segment::Key(_))).count();
+            Some(current.iter().cloned().chain(path[path.len() - own..].iter().cloned().map(Segment::Key)).collect())
+        },
+        Item::Trivia(_) => None,
+    }).collect()
+}
+
+fn push_trivia(items: &mut Vec<Item>, raw: &str) {
+    match items.last_mut() {
+        Some(Item::Trivia(trivia)) => trivia.push_str(raw),
+        _ => items.push(Item::Trivia(raw.to_string())),
+    }
+}
+
+/// splits an already validated document into items, the existing parsers are run over each line to find where keys and values end
+fn segment(text: &str) -> Result<Vec<Item>, ParserError> {
+    let mut items = Vec::new();
+    let mut current: Vec<Key> = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let line_len = rest.find(NEWLINE_LF).map_or(rest.len(), |i| i + 1);
+        let trimmed = rest[..line_len].trim_start_matches([WHITESPACE_SPACE, WHITESPACE_TAB]);
+
+        let len = if trimmed.is_empty() || trimmed.starts_with([COMMENT_START, NEWLINE_LF, NEWLINE_CR]) {
+            push_trivia(&mut items, &rest[..line_len]);
+            line_len
+        } else if let Some(header) = trimmed.strip_prefix('[') {
+            let header = header.strip_prefix('[').unwrap_or(header);
+            let mut input = ToSupplier::from_string(header);
+            let path = KeyParser::parse_path(None, &mut input, ']')?;
+            let comment = trailing_comment(&header[byte_offset(header, input.position().column)..]);
+
+            current = path.clone();
+            items.push(Item::Header { path, raw: rest[..line_len].to_string(), comment });
+            line_len
+        } else {
+            let mut input = ToSupplier::from_string(rest);
+            let keys = KeyParser::parse_path(None, &mut input, '=')?;
+
+            let first = match skip_whitespaces(&mut input, true) {
+                Some(first) => first,
+                None => return ParserError::from(FormatError::EmptyValue),
+            };
+            let start = byte_offset(rest, input.position().column - 1);
+
+            ValueParser::parse_value(first, &mut input)?;
+            let read = input.position().column;
+            let end = byte_offset(rest, if input.last().is_some() { read - 1 } else { read });
+            let line_end = rest[end..].find(NEWLINE_LF).map_or(rest.len(), |i| end + i + 1);
+
+            let mut path = current.clone();
+            path.extend(keys);
+            items.push(Item::Entry {
+                path,
+                key: rest[..start].to_string(),
+                value: rest[start..end].to_string(),
+                suffix: rest[end..line_end].to_string(),
+            });
+            line_end
+        };
+
+        rest = &rest[len..];
+    }
+
+    Ok(items)
+}
+
+impl Document {
+    /// parses `text`, failing with the same errors as [`crate::parse`]
+    pub fn parse(text: &str) -> Result<Self, ParserError> {
+        crate::parse(text)?;
+
+        // the first line break decides, documents mixing both are rare enough
+        let linebreak = match text.find(NEWLINE_LF) {
+            Some(i) if text[..i].ends_with(NEWLINE_CR) => NEWLINE_CRLF,
+            _ => NEWLINE_LF_STR,
+        };
+
+        Ok(Document {
+            items: segment(text)?,
+            linebreak,
+        })
+    }
+
+    /// the document as a tree of values
+    pub fn table(&self) -> Table {
+        crate::parse(&self.to_string()).expect("edits are validated before they are kept")
+    }
+
+    /// comments of the key or table header at `path`, `None` if the document has neither,
+    /// an element of an array of tables is addressed by its index and stands for its header
+    pub fn comments(&self, path: &[Segment]) -> Option<Comments> {
+        let at = segment_paths(&self.items).iter().position(|item| item.as_deref() == Some(path))?;
+
+        let leading = match at.checked_sub(1).map(|i| &self.items[i]) {
+            Some(Item::Trivia(trivia)) => leading_comments(trivia),
+            _ => Vec::new(),
+        };
+        let trailing = match &self.items[at] {
+            Item::Entry { suffix, .. } => trailing_comment(suffix),
+            Item::Header { comment, .. } => comment.clone(),
+            Item::Trivia(_) => unreachable!(),
+        };
+
+        Some(Comments { leading, trailing })
+    }
+
+    /// comments of every element of the array at `path`, `None` if there is no array written under that key
+    pub fn element_comments(&self, path: &[Segment]) -> Option<Vec<Comments>> {
+        self.items.iter().zip(segment_paths(&self.items)).find_map(|(item, item_path)| match item {
+            Item::Entry { value, .. } if item_path.as_deref() == Some(path) && value.starts_with('[') => {
+                Some(element_comments(value).expect("values are validated before they are kept"))
+            },
+            _ => None,
+        })
+    }
+
+    /// applies `edit` and keeps the result only if the edited text is still a valid document
+    fn edit<T>(&mut self, edit: impl FnOnce(&mut Vec<Item>) -> T) -> Result<T, ParserError> {
+        let backup = self.items.clone();
+        let result = edit(&mut self.items);
+
+        if let Err(err) = crate::parse(&self.to_string()) {
+            self.items = backup;
+            return Err(err);
+        }
+
+        Ok(result)
+    }
+
+    /// sets the value under `path`, an existing value is replaced in place and keeps its key and trailing comment,
+    /// a new key is added at the end of the deepest table that has a header on the way to it, using dotted keys for the rest,
+    /// a root table without keys gets it above the comments of the first header
+    pub fn set(&mut self, path: &[&str], value: &Value) -> Result<(), ParserError> {
+        let path = to_keys(path);
+        let linebreak = self.linebreak;
+        let mut raw = std::string::String::new();
+        writer::write_value(&mut raw, value).expect("writing into a string does not fail");
+
+        self.edit(|items| {
+            let existing = items.iter_mut().rev().find_map(|item| match item {
+                Item::Entry { path: entry, value, .. } if *entry == path => Some(value),
+                _ => None,
+            });
+
+            if let Some(value) = existing {
+                *value = raw;
+                return;
+            }
+
+            // the deepest header which is a parent of `path`, `None` stands for the root table
+            let header = items.iter().enumerate()
+                .filter_map(|(i, item)| match item {
+                    Item::Header { path: header, .. } if header.len() < path.len() && path.starts_with(header) => Some((header.len(), i)),
+                    _ => None,
+                })
+                .max_by_key(|(len, _)| *len);
+
+            let (depth, start) = header.map_or((0, 0), |(len, i)| (len, i + 1));
+            let end = items[start..].iter().position(|item| matches!(item, Item::Header { .. })).map_or(items.len(), |i| start + i);
+
+            let at = match items[start..end].iter().rposition(|item| matches!(item, Item::Entry { .. })) {
+                Some(last) => start + last + 1,
+                None if header.is_some() => start,
+                // a document starting with a header, or one without any
+                None if end == 0 || end == items.len() => end,
+                None => {
+                    // only trivia comes before the first header, its last comment lines belong to the header
+                    let Item::Trivia(trivia) = &items[end - 1] else {
+                        unreachable!("the root table holds no entries");
+                    };
+                    let cut = attached_start(trivia);
+
+                    if cut == 0 {
+                        end - 1
+                    } else if cut < trivia.len() {
+                        let attached = trivia[cut..].to_string();
+                        items[end - 1] = Item::Trivia(trivia[..cut].to_string());
+                        items.insert(end, Item::Trivia(attached));
+                        end
+                    } else {
+                        end
+                    }
+                },
+            };
+
+            if at > 0 && !items[at - 1].ends_with_linebreak() {
+                items[at - 1].push_linebreak(linebreak);
+            }
+
+            // new keys line up with the entry they follow
+            let mut key = match at.checked_sub(1).map(|i| &items[i]) {
+                Some(Item::Entry { key, .. }) => key.chars().take_while(|c| [WHITESPACE_SPACE, WHITESPACE_TAB].contains(c)).collect(),
+                _ => std::string::String::new(),
+            };
+            for (i, segment) in path[depth..].iter().enumerate() {
+                if i > 0 {
+                    key.push('.');
+                }
+                writer::write_key(&mut key, segment).expect("writing into a string does not fail");
+            }
+            key.push_str(" = ");
+
+            items.insert(at, Item::Entry { path, key, value: raw, suffix: linebreak.to_string() });
+        })
+    }
+
+    /// appends an empty `[path]` table at the end of the document
+    pub fn insert_table(&mut self, path: &[&str]) -> Result<(), ParserError> {
+        let path = to_keys(path);
+        let linebreak = self.linebreak;
+
+        self.edit(|items| {
+            let mut raw = std::string::String::new();
+            if let Some(last) = items.last_mut() {
+                if !last.ends_with_linebreak() {
+                    last.push_linebreak(linebreak);
+                }
+                raw.push_str(linebreak);
+            }
+
+            raw.push('[');
+            for (i, segment) in path.iter().enumerate() {
+                if i > 0 {
+                    raw.push('.');
+                }
+                writer::write_key(&mut raw, segment).expect("writing into a string does not fail");
+            }
+            raw.push(']');
+            raw.push_str(linebreak);
+
+            items.push(Item::Header { path, raw, comment: None });
+        })
+    }
+
+    /// removes the key or table under `path` along with everything below it,
+    /// tables go together with their headers and the comments inside them, returns whether anything was removed
+    ///
+    /// comment lines directly above a removed key or header go with it, the ones above whatever follows are kept
+    pub fn remove(&mut self, path: &[&str]) -> Result<bool, ParserError> {
+        let path = to_keys(path);
+
+        self.edit(|items| {
+            let mut is_removed_section = false;
+            let removed: Vec<bool> = items.iter().map(|item| match item {
+                Item::Header { path: header, .. } => {
+                    is_removed_section = header.starts_with(&path);
+                    is_removed_section
+                },
+                Item::Entry { path: entry, .. } => is_removed_section || entry.starts_with(&path),
+                Item::Trivia(_) => is_removed_section,
+            }).collect();
+
+            let any = items.iter().zip(&removed).any(|(item, removed)| *removed && !matches!(item, Item::Trivia(_)));
+
+            let mut kept = Vec::with_capacity(items.len());
+            for (i, item) in std::mem::take(items).into_iter().enumerate() {
+                let Item::Trivia(trivia) = item else {
+                    if !removed[i] {
+                        kept.push(item);
+                    }
+                    continue;
+                };
+
+                // trivia is split at its last blank line, the lines below it go with the next key or header
+                let cut = if i + 1 < removed.len() { attached_start(&trivia) } else { trivia.len() };
+                let parts = [(&trivia[..cut], removed[i]), (&trivia[cut..], removed.get(i + 1).copied().unwrap_or(removed[i]))];
+                for (part, removed) in parts {
+                    if !removed && !part.is_empty() {
+                        push_trivia(&mut kept, part);
+                    }
+                }
+            }
+
+            *items = kept;
+            any
+        })
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for item in &self.items {
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Document {
+    type Err = ParserError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Document::parse(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited(text: &str, edit: impl FnOnce(&mut Document)) -> std::string::String {
+        let mut document = Document::parse(text).unwrap();
+        edit(&mut document);
+        document.to_string()
+    }
+
+    #[test]
+    fn round_trip_is_byte_exact() {
+        let text = "# top\n\ntitle =   'x' # why\n\n[server]   # main\n  port = 8_080\n\"a.b\" . c = [ 1,\n  2, # two\n]\r\n\n[[w]]\nn = 1\n[[w]]\nn = 2";
+        assert_eq!(edited(text, |_| {}), text);
+    }
+
+    #[test]
+    fn set_replaces_value_in_place() {
+        let text = "[server]\nport = 1 # keep\n";
+        let result = edited(text, |doc| doc.set(&["server", "port"], &Value::String("x".into())).unwrap());
+        assert_eq!(result, "[server]\nport = \"x\" # keep\n");
+    }
+
+    #[test]
+    fn set_appends_to_table() {
+        let text = "[t]\nx = 1\n\n# about u\n[u]\n";
+        let result = edited(text, |doc| doc.set(&["t", "y"], &Value::Boolean(true)).unwrap());
+        assert_eq!(result, "[t]\nx = 1\ny = true\n\n# about u\n[u]\n");
+    }
+
+    #[test]
+    fn set_root_key_before_header_comments() {
+        let result = edited("# hdr\n[t]\nx = 1", |doc| doc.set(&["y"], &Value::Boolean(true)).unwrap());
+        assert_eq!(result, "y = true\n# hdr\n[t]\nx = 1");
+
+        let result = edited("# file\n\n# hdr\n[t]\n", |doc| doc.set(&["y"], &Value::Boolean(true)).unwrap());
+        assert_eq!(result, "# file\n\ny = true\n# hdr\n[t]\n");
+
+        let result = edited("a = 1\n\n# hdr\n[t]\n", |doc| doc.set(&["y"], &Value::Boolean(true)).unwrap());
+        assert_eq!(result, "a = 1\ny = true\n\n# hdr\n[t]\n");
+    }
+
+    #[test]
+    fn set_keeps_crlf() {
+        let result = edited("[t]\r\nx = 1", |doc| doc.set(&["t", "y"], &Value::Boolean(true)).unwrap());
+        assert_eq!(result, "[t]\r\nx = 1\r\ny = true\r\n");
+
+        let result = edited("a = 1\r\n", |doc| doc.insert_table(&["t"]).unwrap());
+        assert_eq!(result, "a = 1\r\n\r\n[t]\r\n");
+    }
+
+    #[test]
+    fn remove_keeps_comments_of_next_header() {
+        let result = edited("[server]\nport = 1\n\n# about db\n[db]\n", |doc| assert!(doc.remove(&["server"]).unwrap()));
+        assert_eq!(result, "# about db\n[db]\n");
+    }
+
+    #[test]
+    fn remove_takes_own_comments() {
+        let text = "a = 1\n\n# about b\nb = 2\n# about c\nc = 3\n";
+        let result = edited(text, |doc| assert!(doc.remove(&["b"]).unwrap()));
+        assert_eq!(result, "a = 1\n\n# about c\nc = 3\n");
+
+        let text = "a = 1\n\n# about s\n[s]\nx = 1\n\n# about t\n[t]\n";
+        let result = edited(text, |doc| assert!(doc.remove(&["s"]).unwrap()));
+        assert_eq!(result, "a = 1\n\n# about t\n[t]\n");
+    }
+
+    fn at(path: &[&str]) -> Vec<Segment> {
+        path.iter().map(|segment| match segment.parse() {
+            Ok(i) => Segment::Index(i),
+            Err(_) => Segment::Key(Key::new(segment.to_string())),
+        }).collect()
+    }
+
+    fn comment(leading: &[&str], trailing: Option<&str>) -> Comments {
+        Comments { leading: leading.iter().map(|line| line.to_string()).collect(), trailing: trailing.map(str::to_string) }
+    }
+
+    #[test]
+    fn comments_of_keys_and_headers() {
+        let document = Document::parse("# detached
+
+# a
+# b
+x = 1 # x
+[t] # t
+y.z = 2
+").unwrap();
+
+        assert_eq!(document.comments(&at(&["x"])), Some(comment(&[" a", " b"], Some(" x"))));
+        assert_eq!(document.comments(&at(&["t"])), Some(comment(&[], Some(" t"))));
+        assert_eq!(document.comments(&at(&["t", "y", "z"])), Some(comment(&[], None)));
+        assert_eq!(document.comments(&at(&["y"])), None);
+    }
+
+    #[test]
+    fn comments_of_every_array_of_tables_element() {
+        let document = Document::parse("# first
+[[w]]
+n = 1 # one
+[[w.v]] # inner
+# second
+[[w]]
+n = 2
+[[w.v]]
+").unwrap();
+
+        assert_eq!(document.comments(&at(&["w", "0"])), Some(comment(&[" first"], None)));
+        assert_eq!(document.comments(&at(&["w", "1"])), Some(comment(&[" second"], None)));
+        assert_eq!(document.comments(&at(&["w", "0", "n"])), Some(comment(&[], Some(" one"))));
+        assert_eq!(document.comments(&at(&["w", "0", "v", "0"])), Some(comment(&[], Some(" inner"))));
+        assert_eq!(document.comments(&at(&["w", "1", "v", "0"])), Some(comment(&[], None)));
+        assert_eq!(document.comments(&at(&["w", "2"])), None);
+    }
+
+    #[test]
+    fn comments_of_array_elements() {
+        let document = Document::parse("[[w]]
+a = [1]
+[[w]]
+a = [
+  # one
+  1, # first
+  [2, # nested
+  ],
+]
+").unwrap();
+
+        assert_eq!(document.element_comments(&at(&["w", "0", "a"])), Some(vec![comment(&[], None)]));
+        assert_eq!(document.element_comments(&at(&["w", "1", "a"])), Some(vec![
+            comment(&[" one"], Some(" first")),
+            comment(&[" nested"], None),
+        ]));
+    }
+
+    #[test]
+    fn remove_nothing() {
+        let text = "a = 1\n# end\n";
+        let result = edited(text, |doc| assert!(!doc.remove(&["b"]).unwrap()));
+        assert_eq!(result, text);
+    }
+}
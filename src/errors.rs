@@ -1,14 +1,20 @@
 use std::fmt::{Debug, Display};
 use core::error::Error;
 
-use crate::reader::char_supplier::DebuggingIterator;
+use crate::codes::ErrorCode;
+use crate::suggest::{suggest, Suggestion};
+use crate::reader::char_supplier::{DebuggingIterator, Position, Span, Supplier};
+use crate::types::Segment;
+use crate::CharExt;
 
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum UnallowedCharacterReason {
     // InLine,
     InComment,
     InTypeNumber,
+    InTypeRadixInteger,
     InTypeBoolean,
     InTypeBasicString,
     InTypeMultilineBasicString,
@@ -19,6 +25,23 @@ pub enum UnallowedCharacterReason {
     InTypeTime,
     InTypeDateTime,
     InKey,
+    InInlineTable,
+}
+
+#[derive(Debug)]
+pub enum UnderscoreReason {
+    Leading,
+    Trailing,
+    Repeated,
+    NotBetweenDigits,
+}
+
+#[derive(Debug)]
+pub enum NumberReason {
+    LeadingZero,
+    MissingIntegerPart,
+    MissingFractionalPart,
+    MissingExponent,
 }
 
 #[derive(Debug)]
@@ -27,8 +50,18 @@ pub enum FormatError {
     ExpectedCharacter(char),
     ExpectedSequence(std::string::String),
     UnknownEscapeSequence,
+    MisplacedUnderscore(std::string::String, UnderscoreReason),
+    MalformedNumber(std::string::String, NumberReason),
+    MalformedBoolean(std::string::String),
+    IntegerOverflow(std::string::String),
+    InvalidDateTime(std::string::String),
     EmptyValue,
     UnexpectedEnd,
+    DuplicateKey(std::string::String, Position, Position),
+    DuplicateTable(std::string::String, Position, Position),
+    ExtendedInlineTable(std::string::String, Position, Position),
+    ExtendedTableWithDottedKeys(std::string::String, Position, Position),
+    ExtendedStaticArray(std::string::String, Position, Position),
     Unknown(std::string::String)
 }
 
@@ -38,6 +71,7 @@ impl Display for FormatError {
             FormatError::UnallowedCharacter(c, reason) => {
                 let reason = match reason {
                     UnallowedCharacterReason::InTypeNumber => "in a number",
+                    UnallowedCharacterReason::InTypeRadixInteger => "in a hexadecimal, octal or binary integer",
                     // UnallowedCharacterReason::InLine => "in line",
                     UnallowedCharacterReason::InComment => "in a comment",
                     UnallowedCharacterReason::InTypeBoolean => "in a boolean",
@@ -50,14 +84,41 @@ impl Display for FormatError {
                     UnallowedCharacterReason::InTypeTime => "in a time value",
                     UnallowedCharacterReason::InTypeDateTime => "in a date-time value",
                     UnallowedCharacterReason::InKey => "in key",
+                    UnallowedCharacterReason::InInlineTable => "in an inline table",
                 };
                 write!(f, "unexpected character `{c}` {reason}")
             },
+            FormatError::IntegerOverflow(literal) => write!(f, "integer `{literal}` is out of range, integers must be between {} and {}", i64::MIN, i64::MAX),
+            FormatError::InvalidDateTime(part) => write!(f, "`{part}` is not a valid date, time or offset"),
             FormatError::ExpectedCharacter(c) => write!(f, "expected character `{c}`"),
             FormatError::UnknownEscapeSequence => write!(f, "unknown escape sequence"),
+            FormatError::MisplacedUnderscore(literal, reason) => {
+                let reason = match reason {
+                    UnderscoreReason::Leading => "cannot start with an underscore",
+                    UnderscoreReason::Trailing => "cannot end with an underscore",
+                    UnderscoreReason::Repeated => "cannot have consecutive underscores",
+                    UnderscoreReason::NotBetweenDigits => "can only have underscores between two digits",
+                };
+                write!(f, "number `{literal}` {reason}")
+            },
+            FormatError::MalformedNumber(literal, reason) => {
+                let reason = match reason {
+                    NumberReason::LeadingZero => "cannot have leading zeros",
+                    NumberReason::MissingIntegerPart => "needs at least one digit before the decimal point",
+                    NumberReason::MissingFractionalPart => "needs at least one digit after the decimal point",
+                    NumberReason::MissingExponent => "needs at least one digit in the exponent",
+                };
+                write!(f, "number `{literal}` {reason}")
+            },
+            FormatError::MalformedBoolean(literal) => write!(f, "`{literal}` is not a boolean, booleans are either `true` or `false`"),
             FormatError::EmptyValue => write!(f, "empty value"),
             FormatError::UnexpectedEnd => write!(f, "unexpected end of file"),
             FormatError::ExpectedSequence(seq) => write!(f, "expected `{seq}`"),
+            FormatError::DuplicateKey(path, first, second) => write!(f, "key `{path}` at {second} is already defined at {first}"),
+            FormatError::DuplicateTable(path, first, second) => write!(f, "table `{path}` at {second} is already defined at {first}"),
+            FormatError::ExtendedInlineTable(path, first, second) => write!(f, "inline table `{path}` defined at {first} cannot be extended at {second}"),
+            FormatError::ExtendedTableWithDottedKeys(path, first, second) => write!(f, "table `{path}` created by a header at {first} cannot be extended with dotted keys at {second}"),
+            FormatError::ExtendedStaticArray(path, first, second) => write!(f, "array `{path}` defined at {first} cannot be extended as an array of tables at {second}"),
             FormatError::Unknown(seq) => write!(f, "unknown error: {seq}"),
         }
     }
@@ -65,52 +126,273 @@ impl Display for FormatError {
 
 impl Error for FormatError {}
 
+/// a value of the document does not fit the type it is read into, or a value cannot be written as TOML
+#[derive(Debug)]
+pub struct DataError {
+    /// dotted path of the offending value, empty for the root table
+    pub path: std::string::String,
+    pub message: std::string::String,
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} for key `{}`", self.message, self.path)
+        }
+    }
+}
+
+impl Error for DataError {}
+
+/// what went wrong, the finer details are left to the wrapped error
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// the document is not valid TOML
+    Format(FormatError),
+    /// the document is valid but does not fit the type it is read into
+    Data(DataError),
+    /// a literal that passed the checks of the parser was still refused when converting it
+    Other(Box<dyn Error + Send + Sync>),
+    /// the input could not be read, either because of an I/O error or because it is not UTF-8
+    Read(std::io::Error),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Format(err) => Display::fmt(err, f),
+            ErrorKind::Data(err) => Display::fmt(err, f),
+            ErrorKind::Other(err) => Display::fmt(err, f),
+            ErrorKind::Read(err) => write!(f, "input could not be read: {err}"),
+        }
+    }
+}
+
+impl From<FormatError> for ErrorKind {
+    fn from(value: FormatError) -> Self {
+        ErrorKind::Format(value)
+    }
+}
+
+impl From<DataError> for ErrorKind {
+    fn from(value: DataError) -> Self {
+        ErrorKind::Data(value)
+    }
+}
+
+impl From<std::io::Error> for ErrorKind {
+    fn from(value: std::io::Error) -> Self {
+        ErrorKind::Read(value)
+    }
+}
+
+impl From<std::num::ParseIntError> for ErrorKind {
+    fn from(value: std::num::ParseIntError) -> Self {
+        ErrorKind::Other(Box::new(value))
+    }
+}
+
+impl From<std::num::ParseFloatError> for ErrorKind {
+    fn from(value: std::num::ParseFloatError) -> Self {
+        ErrorKind::Other(Box::new(value))
+    }
+}
+
+impl From<std::str::ParseBoolError> for ErrorKind {
+    fn from(value: std::str::ParseBoolError) -> Self {
+        ErrorKind::Other(Box::new(value))
+    }
+}
+
+/// what the parser was in the middle of when the error was found, paths are given from the root of the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Context {
+    /// reading the element at the index of the array at the path
+    ArrayElement(Vec<Segment>, usize),
+    /// reading the inline table at the path
+    InlineTable(Vec<Segment>),
+    /// reading the value of the key at the path
+    Value(Vec<Segment>),
+    /// reading a `[header]` or `[[header]]`
+    Header,
+}
+
+impl Context {
+    fn path_mut(&mut self) -> Option<&mut Vec<Segment>> {
+        match self {
+            Context::ArrayElement(path, _) | Context::InlineTable(path) | Context::Value(path) => Some(path),
+            Context::Header => None,
+        }
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Context::ArrayElement(path, i) => write!(f, "in array element {i} of `{}`", Segment::join(path)),
+            Context::InlineTable(path) => write!(f, "in inline table `{}`", Segment::join(path)),
+            Context::Value(path) => write!(f, "in the value of `{}`", Segment::join(path)),
+            Context::Header => write!(f, "in a table header"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ParserError {
-    source: Box<dyn Error>,
-    // offset: usize,
+    // boxed to keep the `Result`s returned all over the parser small
+    kind: Box<ErrorKind>,
+    /// where in the input the error was found, before the error is located only the start of the innermost key or value
+    /// it was found in is known and the span is empty
+    span: Option<Span>,
+    /// the key being parsed, as far as it is known
+    path: Vec<Segment>,
+    /// innermost first
+    context: Vec<Context>,
+    suggestion: Option<Box<Suggestion>>,
 }
 
 impl ParserError {
-    pub fn from<T,E: Error + 'static>(source: E) -> Result<T,ParserError> {
+    pub(crate) fn from<T,E: Into<ErrorKind>>(source: E) -> Result<T,ParserError> {
         Err(ParserError {
-            source: Box::new(source),
+            kind: Box::new(source.into()),
+            span: None,
+            path: Vec::new(),
+            context: Vec::new(),
+            suggestion: None,
         })
     }
 
-    pub fn extend<T>(source: Self) -> Result<T,Self> {
+    pub(crate) fn extend<T>(source: Self) -> Result<T,Self> {
         Err(ParserError {
             ..source
         })
     }
 
-    pub fn explain_with_debug<R: std::io::Read>(&self, iter: &mut DebuggingIterator<'_,R>) {
-        let needle = iter.get_needle();
+    /// points the error at the key or value it was found in, from its start up to the last character read by `iter`,
+    /// and reads on to the end of its line, which is also where a suggestion for the line is worked out
+    pub(crate) fn located<R: std::io::Read>(self, iter: &mut DebuggingIterator<'_,R>) -> Self {
+        let position = Supplier::position(iter);
+        let start = self.span.map_or(position, |span| span.start);
+
+        // the character the error was found at is left out when it merely ends the token
+        let end = match Supplier::last(iter) {
+            Some(c) if c.is_linebreak() => position,
+            Some(c) if start == position || !(CharExt::is_whitespace(&c) || [',', ']', '}', '#'].contains(&c)) => position.after(c),
+            _ => position,
+        };
         let line = iter.get_last_line();
+        let suggestion = suggest(&self.kind, &self.context, line, Span { start, end }).map(Box::new);
+
+        ParserError {
+            suggestion,
+            ..self.at(Span { start, end })
+        }
+    }
+
+    /// the error reading the input stopped with, pointed at where it happened, `None` if nothing went wrong so far
+    pub(crate) fn unreadable<R: std::io::Read>(iter: &mut DebuggingIterator<'_,R>) -> Option<Self> {
+        let (source, position) = iter.take_error()?;
+
+        Some(ParserError::from::<(), _>(source).unwrap_err().at(Span { start: position, end: position }))
+    }
+
+    /// marks `start` as where the key or value the error was found in starts, unless a nested one already did
+    pub(crate) fn starting_at(self, start: Position) -> Self {
+        ParserError {
+            span: self.span.or(Some(Span { start, end: start })),
+            ..self
+        }
+    }
 
-        println!("{}", line.trim_end());
+    pub(crate) fn at(self, span: Span) -> Self {
+        ParserError {
+            span: Some(span),
+            ..self
+        }
+    }
+
+    /// prepends `segment` to the path of the error and of its context, used while the error travels up from nested values
+    pub(crate) fn under(mut self, segment: Segment) -> Self {
+        self.path.insert(0, segment.clone());
+        for context in &mut self.context {
+            if let Some(path) = context.path_mut() {
+                path.insert(0, segment.clone());
+            }
+        }
+        self
+    }
+
+    /// same as [`ParserError::under`] for every segment of `path`
+    pub(crate) fn under_path(self, path: &[Segment]) -> Self {
+        path.iter().rev().fold(self, |err, segment| err.under(segment.clone()))
+    }
+
+    /// adds `context` as the outermost one so far, its path is relative to the table or value the caller is parsing
+    pub(crate) fn within(mut self, context: Context) -> Self {
+        self.context.push(context);
+        self
+    }
 
-        let mut underline = vec![' '; needle.1 - 1];
-        underline.push('^');
-        let underline = String::from_iter(underline.iter());
-        println!("{}", underline);
+    /// the stable code of the error, see [`ErrorCode::explain`] for what it stands for
+    pub fn code(&self) -> ErrorCode {
+        self.kind.code()
+    }
+
+    /// a likely fix, only known for errors read along with their line
+    pub fn suggestion(&self) -> Option<&Suggestion> {
+        self.suggestion.as_deref()
+    }
 
-        println!("{}", self)
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
     }
 
-    pub fn explain(&self) {
-        println!("{}", self)
+    /// where in the input the error was found, if known
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// position of the last character read before the error, if known
+    pub fn position(&self) -> Option<Position> {
+        self.span().map(|span| span.start)
+    }
+
+    /// the key that was being parsed, empty when the error is not tied to a key
+    pub fn path(&self) -> &[Segment] {
+        &self.path
+    }
+
+    /// what the parser was in the middle of, innermost first
+    pub fn context(&self) -> &[Context] {
+        &self.context
     }
 }
 
 impl Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Failed to parse value: ")?;
-        Display::fmt(&self.source, f)
+        Display::fmt(&self.kind, f)?;
+
+        // the innermost context says the most, the rest is left to `context()`
+        match self.context.first() {
+            Some(context) => write!(f, ", {context}"),
+            None => Ok(()),
+        }
     }
 }
 
-impl Error for ParserError {}
+impl Error for ParserError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind.as_ref() {
+            ErrorKind::Format(err) => Some(err),
+            ErrorKind::Data(err) => Some(err),
+            ErrorKind::Other(err) => Some(err.as_ref()),
+            ErrorKind::Read(err) => Some(err),
+        }
+    }
+}
 
 impl From<std::num::ParseIntError> for ParserError {
     fn from(value: std::num::ParseIntError) -> Self {
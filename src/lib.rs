@@ -0,0 +1,85 @@
+//! A TOML parser, documents are read into a tree of [`Value`]s rooted at a [`Table`] and can be written back with [`to_toml`]
+
+mod errors;
+mod codes;
+mod reader;
+mod common;
+mod types;
+mod consts;
+mod parsers;
+mod writer;
+mod edit;
+mod report;
+mod suggest;
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+mod ser;
+
+pub(crate) use consts::*;
+pub(crate) use common::*;
+
+#[cfg(feature = "serde")]
+pub use de::from_str;
+#[cfg(feature = "serde")]
+pub use ser::{to_string, to_string_pretty};
+pub use codes::{ErrorCode, Explanation, UnknownCode};
+pub use edit::{Comments, Document};
+pub use errors::{Context, DataError, ErrorKind, FormatError, NumberReason, ParserError, UnallowedCharacterReason, UnderscoreReason};
+pub use reader::char_supplier::{Position, Span};
+pub use report::Report;
+pub use suggest::{Edit, Suggestion};
+pub use types::{DateTimeType, Fraction, Key, Location, NumberType, Offset, Segment, Spans, Table, Value};
+
+use crate::{parsers::DocumentParser, reader::char_supplier::Reader};
+
+/// parses a whole document held in memory
+pub fn parse(input: &str) -> Result<Table, ParserError> {
+    from_reader(input.as_bytes())
+}
+
+/// parses a whole document read from `reader`, on failure the error points at where it was found
+pub fn from_reader(reader: impl std::io::Read) -> Result<Table, ParserError> {
+    from_reader_with_spans(reader).map(|(root, _)| root)
+}
+
+/// parses a whole document held in memory along with where each of its keys, values, tables and array elements was written
+pub fn parse_with_spans(input: &str) -> Result<(Table, Spans), ParserError> {
+    from_reader_with_spans(input.as_bytes())
+}
+
+/// same as [`from_reader`] along with the [`Spans`] of the document
+pub fn from_reader_with_spans(reader: impl std::io::Read) -> Result<(Table, Spans), ParserError> {
+    let mut reader = Reader::new(reader);
+    let mut supplier = reader.iter_with_debug();
+
+    let parsed = DocumentParser::parse(&mut supplier);
+
+    // running out of input early is what makes the parser fail when the reader does
+    match ParserError::unreadable(&mut supplier) {
+        Some(err) => Err(err),
+        None => parsed.map_err(|err| err.located(&mut supplier)),
+    }
+}
+
+/// parses as much of a document held in memory as possible, see [`from_reader_recovering`]
+pub fn parse_recovering(input: &str) -> (Table, Vec<ParserError>) {
+    from_reader_recovering(input.as_bytes())
+}
+
+/// reads the whole document even past errors, the line an error is found on is skipped and parsing goes on with the next one,
+/// the table holds everything read successfully and the errors come in the order they were found
+pub fn from_reader_recovering(reader: impl std::io::Read) -> (Table, Vec<ParserError>) {
+    let mut reader = Reader::new(reader);
+    let mut supplier = reader.iter_with_debug();
+
+    let (root, _, errors) = DocumentParser::parse_recovering(&mut supplier);
+    (root, errors)
+}
+
+/// writes `root` back as a TOML document
+pub fn to_toml(root: &Table) -> String {
+    let mut out = String::new();
+    writer::write_document(&mut out, root, false).expect("writing into a string does not fail");
+    out
+}
@@ -1,46 +1,120 @@
-mod errors;
-mod reader;
-mod common;
-mod types;
-mod consts;
-mod parsers;
-
-pub use consts::*;
-pub use common::*;
-
-use crate::{parsers::{KeyParser, ValueParser}, reader::char_supplier::{Reader, Supplier}, types::{NumberType, Value, Entry}};
-use std::collections::hash_map::HashMap;
-
-fn main() {
-    let f = std::fs::File::open("input.toml").unwrap();
-    let mut reader = Reader::new(f);
-    let mut supplier = reader.iter_with_debug();
-
-    // let mut map = std::vec::Vec::new();
-    // while !supplier.is_end() {
-    //     let mut keys = match KeyParser::parse_path(&mut supplier) {
-    //         Ok(_keys) => _keys,
-    //         Err(err) => { 
-    //             err.explain_with_debug(&mut supplier);
-    //             continue;
-    //         },
-    //     };
-    //
-    //     
-    //     match ValueParser::parse(&mut supplier) {
-    //         Ok(wrapped_value) => match wrapped_value {
-    //             Value::Boolean(value) => { println!("{:?} = {}", keys, value); },
-    //             Value::Number(num) => match num {
-    //                             NumberType::Float(value) => { println!("{:?} = {:.1}", keys, value); },
-    //                             NumberType::Integer(value) => { println!("{:?} = {}", keys, value); },
-    //                         },
-    //             Value::String(value) => { println!("{:?} = {}", keys, value); },
-    //             Value::DateTime(value) => println!("{:?} = {}", keys, value.to_string()),
-    //             Value::Nested(hash_map) => todo!(),
-    //         },
-    //         Err(err) => {
-    //             err.explain_with_debug(&mut supplier);
-    //         }
-    //     }
-    // }
+use std::io::{IsTerminal as _, Read as _, Write as _};
+use std::process::ExitCode;
+
+use toml_parser::{parse_recovering, ErrorCode, ParserError, Report};
+
+const USAGE: &str = "\
+usage: toml-parser validate [FILE]...
+       toml-parser explain [CODE]
+
+Parses every FILE as a TOML document and prints a diagnostic for every error found in it.
+With no FILE, or when FILE is `-`, the document is read from standard input.
+
+Diagnostics are coloured when standard error is a terminal, unless NO_COLOR is set.
+
+`explain` describes the error CODE, like T0024, shown in front of every diagnostic.
+With no CODE, every code is listed along with a short description.
+
+exit status: 0 if every document is valid, 1 if any is invalid, 2 on usage or I/O errors";
+
+/// a document that could not be checked at all, as opposed to one that was checked and found invalid
+enum Failure {
+    /// the errors along with the text they were found in
+    Invalid(Vec<ParserError>, String),
+    Unreadable(std::io::Error),
+}
+
+fn validate(path: &str) -> Result<(), Failure> {
+    let mut text = String::new();
+    if path == "-" {
+        std::io::stdin().lock().read_to_string(&mut text).map_err(Failure::Unreadable)?;
+    } else {
+        std::fs::File::open(path).and_then(|mut file| file.read_to_string(&mut text)).map_err(Failure::Unreadable)?;
+    }
+
+    let (_, errors) = parse_recovering(&text);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Failure::Invalid(errors, text))
+    }
+}
+
+fn run_validate(paths: &[String]) -> ExitCode {
+    let stdin = [String::from("-")];
+    let paths = if paths.is_empty() { &stdin[..] } else { paths };
+
+    let mut stderr = std::io::stderr().lock();
+    let color = stderr.is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut any_invalid = false;
+    let mut any_unreadable = false;
+
+    for path in paths {
+        let name = if path == "-" { "<stdin>" } else { path.as_str() };
+
+        match validate(path) {
+            Ok(()) => {},
+            Err(Failure::Invalid(errors, text)) => {
+                let report = Report::new(name, &text).color(color);
+                for err in errors {
+                    let _ = writeln!(stderr, "{}", report.render(&err));
+                }
+                any_invalid = true;
+            },
+            Err(Failure::Unreadable(err)) => {
+                let _ = writeln!(stderr, "{name}: {err}");
+                any_unreadable = true;
+            },
+        }
+    }
+
+    if any_unreadable {
+        ExitCode::from(2)
+    } else if any_invalid {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_explain(codes: &[String]) -> ExitCode {
+    match codes {
+        [] => {
+            for code in ErrorCode::all() {
+                println!("{code}: {}", code.explain().title);
+            }
+            ExitCode::SUCCESS
+        },
+        [code] => match code.parse::<ErrorCode>() {
+            Ok(code) => {
+                print!("{}", code.explain());
+                ExitCode::SUCCESS
+            },
+            Err(err) => {
+                eprintln!("{err}");
+                ExitCode::from(2)
+            },
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        },
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.split_first() {
+        Some((command, paths)) if command == "validate" => run_validate(paths),
+        Some((command, codes)) if command == "explain" => run_explain(codes),
+        Some((flag, _)) if flag == "-h" || flag == "--help" => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        },
+    }
 }
@@ -1,8 +1,17 @@
 use std::any::Any as _;
 
 use super::types;
-use crate::{check_comment_or_whitespaces, errors::{FormatError, ParserError, UnallowedCharacterReason}, reader::char_supplier::{Supplier, ToSupplier}, types::StringType, CharExt};
+use crate::{check_comment_or_whitespaces, errors::{Context, FormatError, ParserError, UnallowedCharacterReason}, reader::char_supplier::{Span, Supplier, ToSupplier}, types::{Location, Segment, StringType}, CharExt, BARE_KEY_CHARS, COMMENT_START, RADIX_PREFIXES};
 
+mod document;
+
+pub use document::DocumentParser;
+
+/// a dotted key along with where each of its segments was written
+pub type SpannedPath = Vec<(types::Key,Span)>;
+
+/// where a value and everything inside it was written, paths are relative to the value which itself has the empty path
+pub type ValueSpans = Vec<(Vec<Segment>, Location)>;
 
 // parse should assume that iterator will read indefinetely, so line breaks should be handled accordingly
 pub trait TypeParser<T> {
@@ -12,19 +21,60 @@ pub trait TypeParser<T> {
 pub struct ValueParser;
 
 impl ValueParser {
-    pub fn parse(input: &mut impl Supplier) -> Result<types::Value,ParserError> {
+    /// parses a value which is expected to be the last thing on the line, except for a comment
+    pub fn parse(input: &mut impl Supplier) -> Result<(types::Value,ValueSpans),ParserError> {
         let c: char = if let Some(_c) = crate::skip_whitespaces(input, true) {
             _c
         } else {
             return ParserError::from(FormatError::EmptyValue)
         };
 
-        let result = if ['"', '\''].contains(&c) {
-            types::String::parse(c, input).map(|v| types::Value::String(v))
+        let value = Self::parse_value(c, input)?;
+
+        match input.last() {
+            Some(c) if c.is_comment_start() || (c.is_whitespace() && !c.is_linebreak()) => {
+                if let Some(err) = check_comment_or_whitespaces(input, c.is_comment_start()) {
+                    return ParserError::extend(err);
+                }
+            },
+            Some(c) if !c.is_linebreak() => return ParserError::from(FormatError::ExpectedCharacter(COMMENT_START)),
+            _ => {},
+        }
+
+        Ok(value)
+    }
+
+    /// parses a single value starting with `first`, after that `input.last()` holds the first character following the value
+    pub fn parse_value(first: char, input: &mut impl Supplier) -> Result<(types::Value,ValueSpans),ParserError> {
+        let start = input.position();
+        let (value, mut spans) = Self::parse_inner(first, input).map_err(|err| err.starting_at(start))?;
+
+        let span = Span { start, end: input.position() };
+        spans.insert(0, (Vec::new(), Location { value: Some(span), ..Location::default() }));
+
+        Ok((value, spans))
+    }
+
+    /// only arrays and inline tables have spans of their own to hand on
+    fn parse_inner(first: char, input: &mut impl Supplier) -> Result<(types::Value,ValueSpans),ParserError> {
+        if first == '[' {
+            types::Array::parse(first, input).map(|(values, spans)| (types::Value::Array(values), spans))
+        } else if first == '{' {
+            types::InlineTable::parse(first, input).map(|(table, spans)| (types::Value::Nested(table), spans))
+        } else {
+            Self::parse_scalar(first, input).map(|value| (value, Vec::new()))
+        }
+    }
+
+    fn parse_scalar(first: char, input: &mut impl Supplier) -> Result<types::Value,ParserError> {
+        let c = first;
+
+        if ['"', '\''].contains(&c) {
+            types::String::parse(c, input).map(types::Value::String)
         } else if ['t', 'f'].contains(&c) {
-            types::Boolean::parse(c, input).map(|v| types::Value::Boolean(v))
-        } else if ['+', '-', '.'].contains(&c) {
-            types::Number::parse(c, input).map(|v| types::Value::Number(v))
+            types::Boolean::parse(c, input).map(types::Value::Boolean)
+        } else if ['+', '-', '.', '_', 'i', 'n'].contains(&c) {
+            types::Number::parse(c, input).map(types::Value::Number)
         } else if c.is_ascii_digit() {
             let mut buf = String::from(c);
             let mut len: u8 = 1;
@@ -34,13 +84,14 @@ impl ValueParser {
 
                     if _c.is_ascii_digit() {
                         buf.push(_c);
+                    } else if len == 2 && c == '0' && RADIX_PREFIXES.iter().any(|(prefix, _)| prefix.ends_with(_c)) {
+                        buf.push(_c);
+                        break Some(types::Number.type_id());
                     } else if (len == 3 && _c == ':') || (len == 5 && _c == '-') {
                         buf.push(_c);
                         break Some(types::DateTime.type_id());
-                    } else if _c == '.' {
-                        buf.push(_c);
-                        break Some(types::Number.type_id());
                     } else {
+                        buf.push(_c);
                         break Some(types::Number.type_id());
                     }
 
@@ -55,9 +106,9 @@ impl ValueParser {
                     let mut buf = ToSupplier::from_string(&buf);
 
                     if type_id == types::Number.type_id() {
-                        types::Number::parse_with_buf(&mut buf, input).map(|v| types::Value::Number(v))
+                        types::Number::parse_with_buf(&mut buf, input).map(types::Value::Number)
                     } else if type_id == types::DateTime.type_id() {
-                        types::DateTime::parse_with_buf(&mut buf, input).map(|v| types::Value::DateTime(v))
+                        types::DateTime::parse_with_buf(&mut buf, input).map(types::Value::DateTime)
                     } else {
                         ParserError::from(FormatError::EmptyValue)
                     }
@@ -67,59 +118,51 @@ impl ValueParser {
             }
         } else {
             ParserError::from(FormatError::EmptyValue)
-        };
-
-        if result.is_ok() {
-            if let Some(c) = input.last().take_if(|c| !c.is_linebreak()) {
-                if let Some(err) = check_comment_or_whitespaces(input, c.is_comment_start()) {
-                    return ParserError::extend(err);
-                }
-            }
         }
-
-        result
     }
 }
 
 pub struct KeyParser;
 
 impl KeyParser {
-    fn parse_segment(first: Option<char>, input: &mut impl Supplier) -> Result<(types::Key,bool),ParserError> {
-        let mut c = if let Some(_c) = first {
-            _c
-        } else {
-            if let Some(_c) = crate::skip_whitespaces(input, false) {
+    fn parse_segment(first: Option<char>, input: &mut impl Supplier, end: char) -> Result<(types::Key,Span,bool),ParserError> {
+        let mut c = match first {
+            Some(_c) if _c.is_linebreak() => return ParserError::from(FormatError::ExpectedCharacter(end)),
+            Some(_c) if !_c.is_whitespace() => _c,
+            _ => if let Some(_c) = crate::skip_whitespaces(input, true) {
                 _c
             } else {
-                return ParserError::from(FormatError::EmptyValue)
-            }
+                return ParserError::from(FormatError::ExpectedCharacter(end))
+            },
         };
         let mut key = std::string::String::new();
+        let start = input.position();
+        let span_end;
 
         if c == '"' || c == '\'' {
-            key = if c == '"' {
-                if let Some(_c) = input.get() {
+            key = if let Some(_c) = input.get() {
+                if c == '"' {
                     StringType::Basic.parse(_c, input)?
                 } else {
-                    return ParserError::from(FormatError::UnexpectedEnd);
-                }
-            } else {
-                if let Some(_c) = input.get() {
                     StringType::Literal.parse(_c, input)?
-                } else {
-                    return ParserError::from(FormatError::UnexpectedEnd);
                 }
+            } else {
+                return ParserError::from(FormatError::UnexpectedEnd);
             };
+            span_end = input.position().after(c);
         } else {
             loop {
-                if c.is_ascii_alphanumeric() || ['_', '-'].contains(&c) {
+                if BARE_KEY_CHARS.contains(&c) {
                     key.push(c);
-                } else if c == '.' || c == '=' {
-                    if key.len() == 0 {
+                } else if c == '.' || c == end {
+                    if key.is_empty() {
                         return ParserError::from(FormatError::EmptyValue);
                     }
-                    return Ok((types::Key::new(key), c == '='));
-                } else if c.is_whitespace() && !c.is_linebreak() {
+                    return Ok((types::Key::new(key), Span { start, end: input.position() }, c == end));
+                } else if c.is_linebreak() {
+                    return ParserError::from(FormatError::ExpectedCharacter(end));
+                } else if c.is_whitespace() {
+                    span_end = input.position();
                     break;
                 } else {
                     return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InKey));
@@ -134,60 +177,53 @@ impl KeyParser {
         }
 
         if let Some(c) = crate::skip_whitespaces(input, true) {
-            if c == '=' || c == '.' {
-                return Ok((types::Key::new(key), c == '='));
+            if c == end || c == '.' {
+                Ok((types::Key::new(key), Span { start, end: span_end }, c == end))
             } else {
-                return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InKey))
+                ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InKey))
             }
         } else {
-            return ParserError::from(FormatError::EmptyValue)
-        };
+            ParserError::from(FormatError::ExpectedCharacter(end))
+        }
     }
 
-}
-
-pub fn parse_entry(input: &mut impl Supplier) -> Result<types::Entry,ParserError> {
-    let mut path: Vec<types::Key> = std::vec::Vec::new();
-
-    let mut c = loop {
-        if let Some(_c) = crate::skip_whitespaces(input, false) {
-            if _c.is_comment_start() {
-                if let Some(err) = check_comment_or_whitespaces(input, true) {
-                    return ParserError::extend(err);
-                }
-                continue;
-            }
-            break Some(_c);
-        } else {
-            return ParserError::from(FormatError::EmptyValue)
-        };
-    };
+    /// reads dotted key segments until `end` is met, `end` itself is consumed
+    pub fn parse_path(first: Option<char>, input: &mut impl Supplier, end: char) -> Result<Vec<types::Key>,ParserError> {
+        Self::parse_spanned_path(first, input, end).map(|path| path.into_iter().map(|(key, _)| key).collect())
+    }
 
-    
-    let (key, is_done) = KeyParser::parse_segment(c, input)?;
+    /// same as [`KeyParser::parse_path`] along with where each segment was written, quotes included
+    pub fn parse_spanned_path(first: Option<char>, input: &mut impl Supplier, end: char) -> Result<SpannedPath,ParserError> {
+        let mut path = Vec::new();
+        let mut first = first;
 
-    let value = if is_done {
-        ValueParser::parse(input)?
-    } else {
-        let mut map = std::boxed::Box::new(std::collections::HashMap::new());
-        let mut inner_map = &mut map;
         loop {
-            let (key, is_done) = KeyParser::parse_segment(None, input)?;
+            let (key, span, is_done) = KeyParser::parse_segment(first.take(), input, end)?;
+            path.push((key, span));
 
             if is_done {
-                let value = ValueParser::parse(input)?;
-                inner_map.insert(key, value);
-                break;
-            } else {
-                let mut new_inner_map = std::boxed::Box::new(std::collections::HashMap::new());
-                inner_map.insert(key, types::Value::Nested(new_inner_map.clone()));
-                inner_map = &mut new_inner_map;
+                break Ok(path);
             }
         }
-
-        types::Value::Nested(map)
     }
+}
+
+/// spans of a dotted key, each prefix of the key covers the segments from the first one up to its own
+pub fn key_spans(path: &[(types::Key,Span)]) -> impl Iterator<Item = (Vec<Segment>,Location)> + '_ {
+    (0..path.len()).map(|i| {
+        let segments = path[..=i].iter().map(|(key, _)| Segment::Key(key.clone())).collect();
+        let span = Span { start: path[0].1.start, end: path[i].1.end };
+
+        (segments, Location { key: Some(span), ..Location::default() })
+    })
+}
 
+/// reads a `key.path = value` line, the key path is returned as is and left for the caller to resolve
+pub fn parse_entry(first: char, input: &mut impl Supplier) -> Result<(SpannedPath,types::Value,ValueSpans),ParserError> {
+    let start = input.position();
+    let path = KeyParser::parse_spanned_path(Some(first), input, '=').map_err(|err| err.starting_at(start))?;
+    let segments: Vec<Segment> = path.iter().map(|(key, _)| Segment::Key(key.clone())).collect();
+    let (value, spans) = ValueParser::parse(input).map_err(|err| err.under_path(&segments).within(Context::Value(segments.clone())))?;
 
-    Ok(types::Entry::new(key, value))
+    Ok((path, value, spans))
 }
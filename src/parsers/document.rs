@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use crate::errors::{Context, FormatError, ParserError};
+use crate::reader::char_supplier::{DebuggingIterator, Position, Span, Supplier};
+use crate::types::{Key, Location, Segment, Spans, Table, Value};
+use crate::{check_comment_or_whitespaces, CharExt, BARE_KEY_CHARS};
+
+use super::{key_spans, parse_entry, KeyParser};
+
+/// identifies a key within the document, elements of arrays of tables are told apart by their index
+type TableId = Vec<(Key, Option<usize>)>;
+
+fn id_to_path(id: &[(Key, Option<usize>)]) -> Vec<Segment> {
+    let mut path = Vec::new();
+    for (key, index) in id {
+        path.push(Segment::Key(key.clone()));
+        if let Some(i) = index {
+            path.push(Segment::Index(*i));
+        }
+    }
+    path
+}
+
+/// how a table came to exist, this decides whether it can be reopened by a header or extended with dotted keys
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum TableKind {
+    /// created on the way to a deeper header, e.g. `a` for `[a.b]`, a later `[a]` may still define it
+    Implicit,
+    /// defined by a `[header]` or appended by a `[[header]]`
+    Header,
+    /// created by a dotted key, only other dotted keys may add to it
+    Dotted,
+    /// written as `{ ... }`, never extended after the closing brace
+    Inline,
+}
+
+/// everything defined so far along with where it was defined, kept apart from the tree so both can be borrowed at once
+struct Definitions {
+    tables: HashMap<TableId, (TableKind, Position)>,
+    keys: HashMap<TableId, Position>,
+}
+
+impl Definitions {
+    fn new() -> Self {
+        Definitions {
+            tables: HashMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    fn defined_at(&self, id: &TableId) -> Position {
+        self.tables.get(id)
+            .map(|(_, position)| *position)
+            .or_else(|| self.keys.get(id).copied())
+            .expect("every key of the tree is recorded")
+    }
+
+    /// walks down from `table` following `path`, missing tables are created as `kind`
+    /// and arrays of tables are entered through their most recently appended element,
+    /// dotted keys may only pass through tables that were created by dotted keys as well
+    fn descend<'a>(&mut self, table: &'a mut Table, id: TableId, path: &[Key], kind: TableKind, position: Position) -> Result<(&'a mut Table, TableId), ParserError> {
+        let mut table = table;
+        let mut id = id;
+
+        for key in path {
+            let is_new = !table.contains_key(key);
+
+            table = match table.entry(key.clone()).or_insert_with(|| Value::Nested(Table::new())) {
+                Value::Nested(inner) => {
+                    id.push((key.clone(), None));
+                    inner
+                },
+                Value::ArrayOfTables(inner) => {
+                    id.push((key.clone(), Some(inner.len() - 1)));
+                    inner.last_mut().expect("array of tables is never empty")
+                },
+                _ => {
+                    id.push((key.clone(), None));
+                    return ParserError::from(FormatError::DuplicateKey(id_to_string(&id), self.defined_at(&id), position));
+                },
+            };
+
+            if is_new {
+                self.tables.insert(id.clone(), (kind, position));
+                continue;
+            }
+
+            match self.tables.get(&id) {
+                Some((TableKind::Inline, first)) => return ParserError::from(FormatError::ExtendedInlineTable(id_to_string(&id), *first, position)),
+                Some((TableKind::Dotted, _)) => {},
+                Some((_, first)) if kind == TableKind::Dotted => return ParserError::from(FormatError::ExtendedTableWithDottedKeys(id_to_string(&id), *first, position)),
+                _ => {},
+            }
+        }
+
+        Ok((table, id))
+    }
+}
+
+fn id_to_string(id: &TableId) -> std::string::String {
+    let path: Vec<Key> = id.iter().map(|(key, _)| key.clone()).collect();
+    Key::join(&path)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OpenString {
+    Basic,
+    Literal,
+    MultilineBasic,
+    MultilineLiteral,
+}
+
+/// what is left open after reading part of an entry, only tells strings, comments and brackets apart
+#[derive(Default)]
+struct OpenValue {
+    /// brackets and braces not closed yet
+    depth: usize,
+    string: Option<OpenString>,
+    /// a run of the same quote not settled yet, as `"`, `""` and `"""` all mean something else
+    quotes: Option<(char, usize)>,
+    is_escaped: bool,
+    is_comment: bool,
+}
+
+impl OpenValue {
+    fn is_open(&self) -> bool {
+        self.depth > 0 || matches!(self.string, Some(OpenString::MultilineBasic | OpenString::MultilineLiteral))
+    }
+
+    fn read(&mut self, c: char) {
+        if let Some((quote, count)) = self.quotes.take() {
+            if c == quote {
+                self.quotes = Some((quote, count + 1));
+                return;
+            }
+            self.settle_quotes(quote, count);
+        }
+
+        if self.is_comment {
+            self.is_comment = !c.is_linebreak();
+            return;
+        }
+
+        match self.string {
+            Some(OpenString::Basic | OpenString::MultilineBasic) if self.is_escaped => self.is_escaped = false,
+            Some(OpenString::Basic | OpenString::MultilineBasic) if c == '\\' => self.is_escaped = true,
+            Some(OpenString::Basic) if c == '"' || c.is_linebreak() => self.string = None,
+            Some(OpenString::Literal) if c == '\'' || c.is_linebreak() => self.string = None,
+            Some(OpenString::MultilineBasic) if c == '"' => self.quotes = Some((c, 1)),
+            Some(OpenString::MultilineLiteral) if c == '\'' => self.quotes = Some((c, 1)),
+            Some(_) => {},
+            None if c == '"' || c == '\'' => self.quotes = Some((c, 1)),
+            None if c.is_comment_start() => self.is_comment = true,
+            None if c == '[' || c == '{' => self.depth += 1,
+            None if c == ']' || c == '}' => self.depth = self.depth.saturating_sub(1),
+            None => {},
+        }
+    }
+
+    /// `count` quotes in a row either open or close a string
+    fn settle_quotes(&mut self, quote: char, count: usize) {
+        let (single, multiline) = if quote == '"' {
+            (OpenString::Basic, OpenString::MultilineBasic)
+        } else {
+            (OpenString::Literal, OpenString::MultilineLiteral)
+        };
+
+        self.string = match self.string {
+            // up to two more quotes can be part of the string right before it is closed
+            Some(_) if count >= 3 => None,
+            Some(open) => Some(open),
+            None if count == 1 => Some(single),
+            // an empty string, or an empty multi-line one
+            None if count == 2 || count >= 6 => None,
+            None => Some(multiline),
+        };
+    }
+}
+
+/// whether `line` starts with a key followed by `=`
+fn starts_entry(line: &str) -> bool {
+    let mut quote = None;
+
+    for (i, c) in line.trim_start().char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {},
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '=' => return i > 0,
+            None if BARE_KEY_CHARS.contains(&c) || c == '.' || CharExt::is_whitespace(&c) => {},
+            None => return false,
+        }
+    }
+
+    false
+}
+
+pub struct DocumentParser {
+    root: Table,
+    /// the table named by the last header, unknown after a header failed to parse
+    current: Option<Vec<Key>>,
+    /// the same table as a path from the root, elements of arrays of tables included
+    current_path: Vec<Segment>,
+    definitions: Definitions,
+    spans: Spans,
+}
+
+impl DocumentParser {
+    fn new() -> Self {
+        DocumentParser {
+            root: Table::new(),
+            current: Some(Vec::new()),
+            current_path: Vec::new(),
+            definitions: Definitions::new(),
+            spans: Spans::new(),
+        }
+    }
+
+    /// parses the whole input into a root table, entries are put into the table declared by the last header,
+    /// along with where each key, value and table was written
+    pub fn parse(input: &mut impl Supplier) -> Result<(Table, Spans), ParserError> {
+        let mut document = DocumentParser::new();
+
+        while let Some(c) = crate::skip_whitespaces(input, false) {
+            document.parse_line(c, input)?;
+        }
+
+        Ok((document.root, document.spans))
+    }
+
+    /// same as [`DocumentParser::parse`] but goes on after an error with the next line, the errors are returned in the order they were found,
+    /// entries below a header that failed are left out since there is no telling which table they belong to
+    pub fn parse_recovering<R: std::io::Read>(input: &mut DebuggingIterator<'_, R>) -> (Table, Spans, Vec<ParserError>) {
+        let mut document = DocumentParser::new();
+        let mut errors = Vec::new();
+
+        loop {
+            input.record();
+            let Some(c) = crate::skip_whitespaces(input, false) else {
+                break;
+            };
+
+            if let Err(err) = document.parse_line(c, input) {
+                if let Some(err) = ParserError::unreadable(input) {
+                    errors.push(err);
+                    break;
+                }
+
+                // locating the error reads up to the end of the line, which is where parsing picks up again
+                // unless the entry opened a value spanning more lines, headers and comments never do
+                errors.push(err.located(input));
+                if c != '[' && !c.is_comment_start() {
+                    Self::skip_open_value(input);
+                }
+            }
+        }
+
+        errors.extend(ParserError::unreadable(input));
+        (document.root, document.spans, errors)
+    }
+
+    /// reads past the lines of an array or multi-line string the broken entry left open, up to the end of the line it is closed on,
+    /// a line looking like the next entry stops it early as the value might never be closed at all
+    fn skip_open_value<R: std::io::Read>(input: &mut DebuggingIterator<'_, R>) {
+        let mut scan = OpenValue::default();
+        for c in input.take_recorded().chars() {
+            scan.read(c);
+        }
+
+        while scan.is_open() {
+            let Some(line) = input.peek_line() else {
+                break;
+            };
+            if scan.string.is_none() && starts_entry(&line) {
+                break;
+            }
+
+            while let Some(c) = input.get() {
+                scan.read(c);
+                if c.is_linebreak() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// handles a comment, a header or an entry starting with `c`
+    fn parse_line(&mut self, c: char, input: &mut impl Supplier) -> Result<(), ParserError> {
+        let position = input.position();
+
+        if c.is_comment_start() {
+            if let Some(err) = check_comment_or_whitespaces(input, true) {
+                return ParserError::extend(err);
+            }
+        } else if c == '[' {
+            self.parse_header(input, position).map_err(|err| err.starting_at(position).within(Context::Header))?;
+        } else {
+            let (path, value, value_spans) = parse_entry(c, input).map_err(|err| err.under_path(&self.current_path))?;
+            let keys: Vec<Key> = path.iter().map(|(key, _)| key.clone()).collect();
+            let segments: Vec<Segment> = keys.iter().cloned().map(Segment::Key).collect();
+
+            let inserted = self.insert(&keys, value, position).map_err(|err| err.starting_at(position).under_path(&segments).under_path(&self.current_path))?;
+            let Some(id) = inserted else {
+                return Ok(());
+            };
+
+            let prefix = id_to_path(&id[..id.len() - keys.len()]);
+            let spans = key_spans(&path).chain(value_spans.into_iter().map(|(relative, location)| {
+                (keys.iter().cloned().map(Segment::Key).chain(relative).collect(), location)
+            }));
+            self.record(&prefix, spans);
+        }
+
+        Ok(())
+    }
+
+    /// merges `spans` given relative to `prefix` into the ones found so far
+    fn record(&mut self, prefix: &[Segment], spans: impl IntoIterator<Item = (Vec<Segment>, Location)>) {
+        for (relative, location) in spans {
+            let path = prefix.iter().cloned().chain(relative).collect();
+            self.spans.entry(path).or_default().merge(location);
+        }
+    }
+
+    /// handles both `[table]` and `[[array.of.tables]]` headers, the opening bracket is already consumed
+    fn parse_header(&mut self, input: &mut impl Supplier, position: Position) -> Result<(), ParserError> {
+        self.current = None;
+        self.current_path.clear();
+
+        let first = input.get();
+        let is_array = first == Some('[');
+
+        let spanned_path = KeyParser::parse_spanned_path(first.filter(|_| !is_array), input, ']')?;
+        let path: Vec<Key> = spanned_path.iter().map(|(key, _)| key.clone()).collect();
+
+        if is_array && input.get() != Some(']') {
+            return ParserError::from(FormatError::ExpectedSequence("]]".to_string()));
+        }
+        let header = Span { start: position, end: input.position().after(']') };
+
+        if let Some(err) = check_comment_or_whitespaces(input, false) {
+            return ParserError::extend(err);
+        }
+
+        let id = if is_array {
+            self.append_table(&path, position)
+        } else {
+            self.open_table(&path, position)
+        };
+        let id = id.map_err(|err| err.under_path(&path.iter().cloned().map(Segment::Key).collect::<Vec<_>>()))?;
+
+        // keys of the header are matched up with the id as it may pass through elements of arrays of tables
+        let mut keys: Vec<_> = key_spans(&spanned_path)
+            .zip(1..)
+            .map(|((_, location), len)| (id_to_path(&id[..len]), location))
+            .collect();
+        let (_, location) = keys.pop().expect("key path is never empty");
+
+        let table = id_to_path(&id);
+        if is_array {
+            // the array itself is found where its first element was appended
+            keys.push((table[..table.len() - 1].to_vec(), location));
+        }
+        self.record(&[], keys);
+        // the header defines the table even when an earlier header already created it on the way to a sub-table
+        self.spans.insert(table.clone(), Location { header: Some(header), ..location });
+        self.current = Some(path);
+        self.current_path = table;
+
+        Ok(())
+    }
+
+    /// defines the table at `path`, tables leading to it are created implicitly
+    fn open_table(&mut self, path: &[Key], position: Position) -> Result<TableId, ParserError> {
+        let (_, id) = self.definitions.descend(&mut self.root, TableId::new(), path, TableKind::Implicit, position)?;
+
+        match self.definitions.tables.get_mut(&id) {
+            Some(definition) if definition.0 == TableKind::Implicit => {
+                *definition = (TableKind::Header, position);
+            },
+            Some((_, first)) => return ParserError::from(FormatError::DuplicateTable(Key::join(path), *first, position)),
+            None => unreachable!("tables are recorded as soon as they are created"),
+        }
+
+        Ok(id)
+    }
+
+    /// pushes a fresh table into the array of tables at `path`, creating the array if needed
+    fn append_table(&mut self, path: &[Key], position: Position) -> Result<TableId, ParserError> {
+        let (key, parent) = path.split_last().expect("key path is never empty");
+        let (table, mut id) = self.definitions.descend(&mut self.root, TableId::new(), parent, TableKind::Implicit, position)?;
+        id.push((key.clone(), None));
+
+        let tables = match table.entry(key.clone()).or_insert_with(|| Value::ArrayOfTables(Vec::new())) {
+            Value::ArrayOfTables(tables) => tables,
+            Value::Array(_) => return ParserError::from(FormatError::ExtendedStaticArray(Key::join(path), self.definitions.defined_at(&id), position)),
+            Value::Nested(_) => return ParserError::from(FormatError::DuplicateTable(Key::join(path), self.definitions.defined_at(&id), position)),
+            _ => return ParserError::from(FormatError::DuplicateKey(Key::join(path), self.definitions.defined_at(&id), position)),
+        };
+
+        tables.push(Table::new());
+        self.definitions.keys.entry(id.clone()).or_insert(position);
+
+        id.last_mut().expect("id has just been extended").1 = Some(tables.len() - 1);
+        self.definitions.tables.insert(id.clone(), (TableKind::Header, position));
+
+        Ok(id)
+    }
+
+    /// puts `value` under the key `path` relative to the current table, tables in between are merged with ones made by earlier dotted keys,
+    /// returns the id of the new key or nothing when the current table is unknown
+    fn insert(&mut self, path: &[Key], value: Value, position: Position) -> Result<Option<TableId>, ParserError> {
+        let (key, parents) = path.split_last().expect("key path is never empty");
+        let Some(current) = &self.current else {
+            return Ok(None);
+        };
+
+        let (table, id) = self.definitions.descend(&mut self.root, TableId::new(), current, TableKind::Implicit, position)?;
+        let (table, mut id) = self.definitions.descend(table, id, parents, TableKind::Dotted, position)?;
+        id.push((key.clone(), None));
+
+        if table.contains_key(key) {
+            return ParserError::from(FormatError::DuplicateKey(id_to_string(&id), self.definitions.defined_at(&id), position));
+        }
+
+        if let Value::Nested(_) = value {
+            self.definitions.tables.insert(id.clone(), (TableKind::Inline, position));
+        } else {
+            self.definitions.keys.insert(id.clone(), position);
+        }
+        table.insert(key.clone(), value);
+
+        Ok(Some(id))
+    }
+}
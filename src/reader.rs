@@ -1,32 +1,82 @@
 pub mod char_supplier {
     use utf8_chars::BufReadCharsExt as _;
 
-    use crate::{CharExt, NEWLINE_CR, NEWLINE_CRLF, NEWLINE_LF_STR};
+    use crate::{CharExt, NEWLINE_CR, NEWLINE_CRLF, NEWLINE_LF, NEWLINE_LF_STR};
 
     pub trait Supplier {
         fn get(&mut self) -> Option<char>;
         fn last(&self) -> Option<char>;
-        fn as_iter(&mut self) -> &mut impl std::iter::Iterator<Item=char>;
+        /// position of the last read character
+        fn position(&self) -> Position;
+    }
+
+    /// line and column of a character in the input, both start at 1, along with its byte offset which starts at 0
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct Position {
+        pub line: usize,
+        pub column: usize,
+        pub offset: usize,
+    }
+
+    impl Position {
+        fn from_needle(needle: (usize,usize), offset: usize) -> Self {
+            Position {
+                line: needle.0 + 1,
+                column: needle.1,
+                offset,
+            }
+        }
+
+        /// the position right behind `c` when `c` is found at this position, `c` must not be a line break
+        pub(crate) fn after(self, c: char) -> Self {
+            Position {
+                column: self.column + 1,
+                offset: self.offset + c.len_utf8(),
+                ..self
+            }
+        }
+    }
+
+    /// the stretch of input from `start` up to `end`, `end` is the position right behind the last character
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct Span {
+        pub start: Position,
+        pub end: Position,
+    }
+
+    impl Span {
+        /// the text covered by the span, `source` has to be the input it was taken from
+        pub fn text<'a>(&self, source: &'a str) -> &'a str {
+            &source[self.start.offset..self.end.offset]
+        }
+    }
+
+    impl std::fmt::Display for Span {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+
+    impl std::fmt::Display for Position {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}:{}", self.line, self.column)
+        }
     }
 
     pub struct Reader<R: std::io::Read> {
         inner: std::io::BufReader<R>,
     }
 
-    impl<'a,R: std::io::Read> Reader<R> {
+    impl<R: std::io::Read> Reader<R> {
         pub fn new(inner: R) -> Reader<R> {
             Self {
                 inner: std::io::BufReader::new(inner)
             }
         }
 
-        pub fn iter_with_debug(&mut self) -> DebuggingIterator<R> {
+        pub fn iter_with_debug(&mut self) -> DebuggingIterator<'_, R> {
             DebuggingIterator::new(self.inner.chars_raw())
         }
-
-        pub fn iter(&mut self) -> Iterator<R> {
-            Iterator::new(self.inner.chars_raw())
-        }
     }
 
     pub struct DebuggingIterator<'a, R: std::io::Read + ?Sized> {
@@ -36,6 +86,15 @@ pub mod char_supplier {
         line_end_buf: std::string::String,
         needle: (usize,usize),
         last: Option<char>,
+        /// bytes read so far and where the last character started, a CRLF line break starts at its CR
+        read: usize,
+        offset: usize,
+        /// characters looked at by [`DebuggingIterator::peek_line`] but not read yet
+        peeked: std::collections::VecDeque<char>,
+        /// every character read since [`DebuggingIterator::record`] was called
+        recorded: Option<std::string::String>,
+        /// what reading stopped with, the input ends right before it
+        error: Option<std::io::Error>,
     }
 
     impl<R: std::io::Read> DebuggingIterator<'_,R> {
@@ -47,105 +106,73 @@ pub mod char_supplier {
                 line_end_buf: std::string::String::with_capacity(2),
                 needle: (0,0),
                 last: None,
+                read: 0,
+                offset: 0,
+                peeked: std::collections::VecDeque::new(),
+                recorded: None,
+                error: None,
             }
         }
 
-        pub fn get_last_line(&mut self) -> &str {
-            while !(self.is_line_end() || self.is_end()) {
-                self.next();
-            }
-            return &self.last_line;
-        }
-
-        pub fn get_needle(&self) -> (usize,usize) {
-            self.needle
+        /// starts keeping every character read from here on, until [`DebuggingIterator::take_recorded`] is called
+        pub(crate) fn record(&mut self) {
+            self.recorded = Some(std::string::String::new());
         }
 
-        pub fn is_end(&self) -> bool {
-            self.end
+        pub(crate) fn take_recorded(&mut self) -> std::string::String {
+            self.recorded.take().unwrap_or_default()
         }
 
-        pub fn is_line_end(&self) -> bool {
-            match self.line_end_buf.as_str() {
-                NEWLINE_CRLF | NEWLINE_LF_STR => true,
-                _ => false,
+        /// the I/O or UTF-8 error reading stopped with along with where it happened, once everything before it was read
+        pub(crate) fn take_error(&mut self) -> Option<(std::io::Error, Position)> {
+            if !self.end {
+                return None;
             }
-        }
 
-        fn new_line(&mut self) {
-            self.last_line.clear();
-            self.needle.1 = 0;
-            self.needle.0 += 1;
-            self.line_end_buf.clear();
+            let position = if self.is_line_end() {
+                Position::from_needle((self.needle.0 + 1, 1), self.read)
+            } else {
+                Position::from_needle((self.needle.0, self.needle.1 + 1), self.read)
+            };
+            self.error.take().map(|error| (error, position))
         }
-    }
 
-    impl<R: std::io::Read> std::iter::Iterator for DebuggingIterator<'_,R> {
-        type Item = char;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.end {
+        /// the next character of the inner reader, an error is kept and ends the input
+        fn read_char(&mut self) -> Option<char> {
+            if self.error.is_some() {
                 return None;
             }
-            
-            match self.inner.next() {
-                Some(Ok(c)) => {
-                    if c.is_linebreak() && !self.is_line_end() {
-                        self.line_end_buf.push(c);
-
-                        if c == NEWLINE_CR {
-                            return self.next();
-                        }
-                    } else {
-                        if self.is_line_end() {
-                            self.new_line();
-                        }
-                        
-                        self.last_line.push(c);
-                        self.needle.1 += 1;
-                    }
 
-                    self.last = Some(c);
+            match self.inner.next()? {
+                Ok(c) => Some(c),
+                Err(err) => {
+                    self.error = Some(err.into_io_error());
+                    None
                 },
-                Some(Err(_)) | None => {
-                    self.end = true;
-                    self.last = None;
-                }
             }
-
-            self.last
         }
-    }
 
-    impl<R: std::io::Read> Supplier for DebuggingIterator<'_,R> {
-        fn get(&mut self) -> Option<char> {
-            self.next()
-        }
+        /// the next line without reading it, line break left out, `None` at the end of input
+        pub(crate) fn peek_line(&mut self) -> Option<std::string::String> {
+            while !self.peeked.iter().any(|c| c.is_linebreak()) {
+                match self.read_char() {
+                    Some(c) => self.peeked.push_back(c),
+                    None => break,
+                }
+            }
 
-        fn last(&self) -> Option<char> {
-            self.last
-        }
-        
-        fn as_iter(&mut self) -> &mut impl std::iter::Iterator<Item=char> {
-            self
+            if self.peeked.is_empty() {
+                None
+            } else {
+                Some(self.peeked.iter().take_while(|c| !c.is_linebreak()).collect())
+            }
         }
-    }
 
-    pub struct Iterator<'a, R: std::io::Read> {
-        end: bool,
-        inner: utf8_chars::CharsRaw<'a, std::io::BufReader<R>>,
-        line_end_buf: std::string::String,
-        last: Option<char>,
-    }
-    
-    impl<R: std::io::Read> Iterator<'_,R> {
-        pub fn new<'a>(inner: utf8_chars::CharsRaw<'a, std::io::BufReader<R>>) -> Iterator<'a, R> {
-            Iterator {
-                inner,
-                end: false,
-                line_end_buf: std::string::String::with_capacity(2),
-                last: None,
+        pub fn get_last_line(&mut self) -> &str {
+            while !(self.is_line_end() || self.is_end()) {
+                self.next();
             }
+            &self.last_line
         }
 
         pub fn is_end(&self) -> bool {
@@ -153,14 +180,18 @@ pub mod char_supplier {
         }
 
         pub fn is_line_end(&self) -> bool {
-            match self.line_end_buf.as_str() {
-                NEWLINE_CRLF | NEWLINE_LF_STR => true,
-                _ => false,
-            }
+            matches!(self.line_end_buf.as_str(), NEWLINE_CRLF | NEWLINE_LF_STR)
+        }
+
+        fn new_line(&mut self) {
+            self.last_line.clear();
+            self.needle.1 = 0;
+            self.needle.0 += 1;
+            self.line_end_buf.clear();
         }
     }
 
-    impl<R: std::io::Read> std::iter::Iterator for Iterator<'_,R> {
+    impl<R: std::io::Read> std::iter::Iterator for DebuggingIterator<'_,R> {
         type Item = char;
 
         fn next(&mut self) -> Option<Self::Item> {
@@ -168,22 +199,46 @@ pub mod char_supplier {
                 return None;
             }
             
-            match self.inner.next() {
-                Some(Ok(c)) => {
-                    if c.is_linebreak() && !self.is_line_end() {
+            let start = self.read;
+
+            let next = match self.peeked.pop_front() {
+                Some(c) => Some(c),
+                None => self.read_char(),
+            };
+
+            match next {
+                Some(c) => {
+                    self.read += c.len_utf8();
+                    if let Some(recorded) = &mut self.recorded {
+                        recorded.push(c);
+                    }
+
+                    // the line ends with the break, the next character starts a new one even if it is a break by itself
+                    if self.is_line_end() {
+                        self.new_line();
+                    }
+
+                    if c.is_linebreak() {
                         self.line_end_buf.push(c);
 
                         if c == NEWLINE_CR {
-                            return self.next();
+                            let next = self.next();
+                            if next == Some(NEWLINE_LF) {
+                                self.offset = start;
+                            }
+                            return next;
                         }
-                    } else if self.is_line_end() {
-                        self.line_end_buf.clear();
+                    } else {
+                        self.last_line.push(c);
+                        self.needle.1 += 1;
                     }
 
+                    self.offset = start;
                     self.last = Some(c);
                 },
-                Some(Err(_)) | None => {
+                None => {
                     self.end = true;
+                    self.offset = self.read;
                     self.last = None;
                 }
             }
@@ -191,8 +246,8 @@ pub mod char_supplier {
             self.last
         }
     }
-    
-    impl<R: std::io::Read> Supplier for Iterator<'_,R> {
+
+    impl<R: std::io::Read> Supplier for DebuggingIterator<'_,R> {
         fn get(&mut self) -> Option<char> {
             self.next()
         }
@@ -200,21 +255,32 @@ pub mod char_supplier {
         fn last(&self) -> Option<char> {
             self.last
         }
-        
-        fn as_iter(&mut self) -> &mut impl std::iter::Iterator<Item=char> {
-            self
+
+        /// line breaks and the end of input are placed right behind the last character of their line
+        fn position(&self) -> Position {
+            let mut position = Position::from_needle(self.needle, self.offset);
+            if self.end || self.last.is_some_and(|c| c.is_linebreak()) {
+                position.column += 1;
+            }
+            position
         }
     }
 
     pub struct ToSupplier<'a> {
         iter: std::str::Chars<'a>,
+        read: usize,
+        read_bytes: usize,
+        offset: usize,
         last: Option<char>
     }
 
     impl ToSupplier<'_> {
-        pub fn from_string(string: &std::string::String) -> impl Supplier {
+        pub fn from_string(string: &str) -> impl Supplier {
             ToSupplier {
                 iter: string.chars(),
+                read: 0,
+                read_bytes: 0,
+                offset: 0,
                 last: None
             }
         }
@@ -223,16 +289,22 @@ pub mod char_supplier {
     impl Supplier for ToSupplier<'_> {
         fn get(&mut self) -> Option<char> {
             self.last = self.iter.next();
+            self.offset = self.read_bytes;
+            if let Some(c) = self.last {
+                self.read += 1;
+                self.read_bytes += c.len_utf8();
+            }
 
-            return self.last
+            self.last
         }
     
         fn last(&self) -> Option<char> {
             self.last
         }
     
-        fn as_iter(&mut self) -> &mut impl std::iter::Iterator<Item=char> {
-            &mut self.iter
+        /// the buffer is treated as a single line
+        fn position(&self) -> Position {
+            Position::from_needle((0, self.read), self.offset)
         }
     }
 }
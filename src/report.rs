@@ -0,0 +1,217 @@
+use std::fmt::Write;
+
+use unicode_width::UnicodeWidthChar as _;
+
+use crate::errors::{ErrorKind, FormatError, ParserError};
+use crate::reader::char_supplier::{Position, Span};
+use crate::{CharExt, WHITESPACE_TAB};
+
+/// tabs are shown as this many spaces so markers line up no matter how the terminal expands them
+const TAB_WIDTH: usize = 4;
+
+const STYLE_ERROR: &str = "\x1b[1;31m";
+const STYLE_BOLD: &str = "\x1b[1m";
+const STYLE_GUTTER: &str = "\x1b[1;34m";
+const STYLE_SECONDARY: &str = "\x1b[1;36m";
+const STYLE_RESET: &str = "\x1b[0m";
+
+/// a marked stretch of the source, the primary label points at the error itself and secondary ones at what it clashes with
+struct Label {
+    span: Span,
+    message: &'static str,
+    is_primary: bool,
+}
+
+/// renders errors along with the lines of the source they point at, marking the offending parts
+pub struct Report<'a> {
+    name: &'a str,
+    source: &'a str,
+    context: usize,
+    color: bool,
+}
+
+impl<'a> Report<'a> {
+    /// `source` has to be the whole text the errors were found in, `name` is shown in front of the position
+    pub fn new(name: &'a str, source: &'a str) -> Self {
+        Report {
+            name,
+            source,
+            context: 2,
+            color: false,
+        }
+    }
+
+    /// how many lines are shown before and after every marked line, 2 by default
+    pub fn context_lines(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// whether the output is highlighted with ANSI escape codes, off by default
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, err: &ParserError) -> String {
+        let mut out = String::new();
+        self.write(&mut out, err).expect("writing into a string does not fail");
+        out
+    }
+
+    /// same as [`Report::render`] but writes into `out`
+    pub fn write(&self, out: &mut impl Write, err: &ParserError) -> std::fmt::Result {
+        let labels = self.labels(err);
+
+        writeln!(out, "{}{}", self.paint(STYLE_ERROR, &format!("error[{}]", err.code())), self.paint(STYLE_BOLD, &format!(": {}", err.kind())))?;
+
+        let lines: Vec<&str> = self.source.lines().collect();
+        let shown = self.shown_lines(&labels, lines.len());
+        let gutter = shown.last().map_or(1, |line| line.to_string().len());
+
+        let arrow = self.paint(STYLE_GUTTER, "-->");
+        match labels.iter().find(|label| label.is_primary) {
+            Some(label) => writeln!(out, "{}{arrow} {}:{}", " ".repeat(gutter), self.name, label.span.start)?,
+            None => writeln!(out, "{}{arrow} {}", " ".repeat(gutter), self.name)?,
+        }
+
+        if !shown.is_empty() {
+            writeln!(out, "{} {}", " ".repeat(gutter), self.paint(STYLE_GUTTER, "|"))?;
+        }
+
+        let mut previous = None;
+        for &number in &shown {
+            if previous.is_some_and(|previous| previous + 1 < number) {
+                writeln!(out, "{}", self.paint(STYLE_GUTTER, "..."))?;
+            }
+            previous = Some(number);
+
+            let line = lines.get(number - 1).copied().unwrap_or_default();
+            let row = format!("{} {} {}", self.paint(STYLE_GUTTER, &format!("{number:>gutter$}")), self.paint(STYLE_GUTTER, "|"), expand_tabs(line));
+            writeln!(out, "{}", row.trim_end())?;
+
+            let mut marked: Vec<&Label> = labels.iter().filter(|label| label.span.start.line == number).collect();
+            marked.sort_by_key(|label| label.span.start.column);
+
+            for label in marked {
+                let (indent, width) = columns(line, label.span);
+                let (marker, style) = if label.is_primary { ('^', STYLE_ERROR) } else { ('-', STYLE_SECONDARY) };
+
+                let mut underline = marker.to_string().repeat(width);
+                if !label.message.is_empty() {
+                    write!(underline, " {}", label.message)?;
+                }
+                writeln!(out, "{} {} {}{}", " ".repeat(gutter), self.paint(STYLE_GUTTER, "|"), " ".repeat(indent), self.paint(style, &underline))?;
+            }
+        }
+
+        if !err.context().is_empty() || err.suggestion().is_some() {
+            writeln!(out, "{} {}", " ".repeat(gutter), self.paint(STYLE_GUTTER, "|"))?;
+        }
+        for context in err.context() {
+            writeln!(out, "{} {} note: {context}", " ".repeat(gutter), self.paint(STYLE_GUTTER, "="))?;
+        }
+        if let Some(suggestion) = err.suggestion() {
+            writeln!(out, "{} {} help: {suggestion}", " ".repeat(gutter), self.paint(STYLE_GUTTER, "="))?;
+        }
+
+        Ok(())
+    }
+
+    /// clashes between definitions point at both places, any other error at where it was found
+    fn labels(&self, err: &ParserError) -> Vec<Label> {
+        let clash = match err.kind() {
+            ErrorKind::Format(FormatError::DuplicateKey(_, first, second)) => Some((first, "first defined here", second, "defined again here")),
+            ErrorKind::Format(FormatError::DuplicateTable(_, first, second)) => Some((first, "first defined here", second, "defined again here")),
+            ErrorKind::Format(FormatError::ExtendedInlineTable(_, first, second)) => Some((first, "inline table defined here", second, "extended here")),
+            ErrorKind::Format(FormatError::ExtendedTableWithDottedKeys(_, first, second)) => Some((first, "table defined here", second, "extended with dotted keys here")),
+            ErrorKind::Format(FormatError::ExtendedStaticArray(_, first, second)) => Some((first, "array defined here", second, "extended as an array of tables here")),
+            _ => None,
+        };
+
+        match clash {
+            Some((first, first_message, second, second_message)) => vec![
+                Label { span: self.token_at(*first), message: first_message, is_primary: false },
+                Label { span: self.token_at(*second), message: second_message, is_primary: true },
+            ],
+            None => err.span()
+                .map(|span| vec![Label { span, message: "", is_primary: true }])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// the key or header starting at `start`, only the position of definitions is kept in errors
+    fn token_at(&self, start: Position) -> Span {
+        let rest = self.source.get(start.offset..).unwrap_or_default();
+
+        let mut end = start;
+        let mut quote = None;
+        let mut brackets = 0;
+        for c in rest.chars() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {},
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c == '[' => brackets += 1,
+                None if c == ']' && brackets > 0 => brackets -= 1,
+                None if brackets == 0 && (CharExt::is_whitespace(&c) || c == '=') => break,
+                None => {},
+            }
+            if c.is_linebreak() {
+                break;
+            }
+
+            end = end.after(c);
+            if brackets == 0 && quote.is_none() && c == ']' {
+                break;
+            }
+        }
+
+        Span { start, end }
+    }
+
+    /// every marked line along with the lines around it, in order
+    fn shown_lines(&self, labels: &[Label], count: usize) -> Vec<usize> {
+        let mut shown: Vec<usize> = labels.iter()
+            .flat_map(|label| {
+                let line = label.span.start.line;
+                line.saturating_sub(self.context).max(1)..=(line + self.context).min(count.max(line))
+            })
+            .collect();
+
+        shown.sort_unstable();
+        shown.dedup();
+        shown
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{style}{text}{STYLE_RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn char_width(c: char) -> usize {
+    if c == WHITESPACE_TAB {
+        TAB_WIDTH
+    } else {
+        c.width().unwrap_or(0)
+    }
+}
+
+fn expand_tabs(line: &str) -> String {
+    line.replace(WHITESPACE_TAB, &" ".repeat(TAB_WIDTH))
+}
+
+/// how far the marker for `span` is indented below `line` and how wide it is, spans running past the line are cut at its end
+fn columns(line: &str, span: Span) -> (usize, usize) {
+    let start = span.start.column.saturating_sub(1);
+    let end = if span.end.line == span.start.line { span.end.column.saturating_sub(1) } else { usize::MAX };
+
+    let indent = line.chars().take(start).map(char_width).sum();
+    let width = line.chars().skip(start).take(end.saturating_sub(start)).map(char_width).sum();
+
+    (indent, usize::max(width, 1))
+}
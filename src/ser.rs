@@ -0,0 +1,439 @@
+use serde::ser::{self, Serialize};
+
+use crate::de::Error;
+use crate::errors::DataError;
+use crate::types::{Key, NumberType, Segment, Table, Value};
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        <Error as serde::de::Error>::custom(msg)
+    }
+}
+
+fn unsupported(what: &str) -> Error {
+    <Error as ser::Error>::custom(format!("{what} cannot be represented in TOML"))
+}
+
+/// writes `value` as a TOML document, only structs and maps with string keys can make up a document
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, DataError> {
+    to_string_with(value, false)
+}
+
+/// same as [`to_string`] but arrays with more than one element are spread over several lines
+pub fn to_string_pretty<T: Serialize + ?Sized>(value: &T) -> Result<String, DataError> {
+    to_string_with(value, true)
+}
+
+fn to_string_with<T: Serialize + ?Sized>(value: &T, pretty: bool) -> Result<String, DataError> {
+    let root = match value.serialize(Serializer).map_err(Error::into_data_error)? {
+        Some(Value::Nested(root)) => root,
+        Some(Value::ArrayOfTables(_) | Value::Array(_)) => return Err(unsupported("a sequence at the root of a document").into_data_error()),
+        Some(_) | None => return Err(unsupported("a plain value at the root of a document").into_data_error()),
+    };
+
+    let mut out = String::new();
+    crate::writer::write_document(&mut out, &root, pretty).expect("writing into a string does not fail");
+    Ok(out)
+}
+
+/// integers outside of `i64` can only be written with the `big-integer` feature
+#[cfg(not(feature = "big-integer"))]
+fn out_of_range(v: impl std::fmt::Display) -> Error {
+    <Error as ser::Error>::custom(format!("integer `{v}` does not fit into a signed 64-bit TOML integer"))
+}
+
+fn table_with(key: &'static str, value: Value) -> Value {
+    let mut table = Table::new();
+    table.insert(Key::new(key.to_string()), value);
+    Value::Nested(table)
+}
+
+/// builds a value tree, `None` stands for a value that is left out, as TOML has no null
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeTable;
+    type SerializeStruct = SerializeTable;
+    type SerializeStructVariant = SerializeVariant<SerializeTable>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Boolean(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Number(NumberType::Integer(v))))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            #[cfg(feature = "big-integer")]
+            Err(_) => Ok(Some(Value::Number(NumberType::BigInteger(v.into())))),
+            #[cfg(not(feature = "big-integer"))]
+            Err(_) => Err(out_of_range(v)),
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        match i128::try_from(v) {
+            Ok(v) => self.serialize_i128(v),
+            #[cfg(feature = "big-integer")]
+            Err(_) => Ok(Some(Value::Number(NumberType::BigInteger(v.into())))),
+            #[cfg(not(feature = "big-integer"))]
+            Err(_) => Err(out_of_range(v)),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Number(NumberType::Float(v))))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Array(v.iter().map(|byte| Value::Number(NumberType::Integer((*byte).into()))).collect())))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported("a unit value"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported(&format!("the unit struct `{name}`")))
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = value.serialize(self)
+            .map_err(|err| err.under(Segment::Key(Key::new(variant.to_string()))))?
+            .ok_or_else(|| unsupported("`None` as the content of a variant").under(Segment::Key(Key::new(variant.to_string()))))?;
+
+        Ok(Some(table_with(variant, value)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeArray { values: Vec::with_capacity(len.unwrap_or_default()) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeVariant { variant, inner: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeTable { table: Table::new(), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeVariant { variant, inner: self.serialize_map(Some(len))? })
+    }
+}
+
+struct SerializeArray {
+    values: Vec<Value>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let index = Segment::Index(self.values.len());
+
+        match value.serialize(Serializer) {
+            Ok(Some(value)) => {
+                self.values.push(value);
+                Ok(())
+            },
+            Ok(None) => Err(unsupported("`None` inside an array").under(index)),
+            Err(err) => Err(err.under(index)),
+        }
+    }
+
+    /// arrays made only of tables become arrays of tables so they are written with `[[headers]]`
+    fn finish(self) -> Value {
+        if !self.values.is_empty() && self.values.iter().all(|value| matches!(value, Value::Nested(_))) {
+            Value::ArrayOfTables(self.values.into_iter().map(|value| match value {
+                Value::Nested(table) => table,
+                _ => unreachable!(),
+            }).collect())
+        } else {
+            Value::Array(self.values)
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+struct SerializeTable {
+    table: Table,
+    key: Option<String>,
+}
+
+impl SerializeTable {
+    /// fields holding `None` are left out of the table
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        match value.serialize(Serializer) {
+            Ok(Some(value)) => {
+                self.table.insert(Key::new(key), value);
+                Ok(())
+            },
+            Ok(None) => Ok(()),
+            Err(err) => Err(err.under(Segment::Key(Key::new(key)))),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeTable {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().expect("a value is only given after its key");
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Nested(self.table)))
+    }
+}
+
+impl ser::SerializeStruct for SerializeTable {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::Nested(self.table)))
+    }
+}
+
+/// a variant with content is written as a table holding a single key named after the variant
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.push(value).map_err(|err| err.under(Segment::Key(Key::new(self.variant.to_string()))))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(table_with(self.variant, self.inner.finish())))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeTable> {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.inner.insert(key.to_string(), value).map_err(|err| err.under(Segment::Key(Key::new(self.variant.to_string()))))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(table_with(self.variant, Value::Nested(self.inner.table))))
+    }
+}
+
+/// keys of a table are always strings, anything else is rejected instead of being converted
+struct KeySerializer;
+
+fn non_string_key() -> Error {
+    <Error as ser::Error>::custom("map keys must be strings")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> { Err(non_string_key()) }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(non_string_key())
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(non_string_key())
+    }
+}
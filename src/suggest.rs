@@ -0,0 +1,225 @@
+use std::fmt::Display;
+
+use crate::errors::{Context, ErrorKind, FormatError};
+use crate::reader::char_supplier::{Position, Span};
+use crate::{BARE_KEY_CHARS, COMMENT_START};
+
+/// replaces the text of `span` with `replacement`, an empty span inserts it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub span: Span,
+    pub replacement: std::string::String,
+}
+
+impl Edit {
+    /// `source` has to be the whole text the edit was made for
+    pub fn apply(&self, source: &str) -> std::string::String {
+        let mut out = source.to_string();
+        out.replace_range(self.span.start.offset..self.span.end.offset, &self.replacement);
+        out
+    }
+}
+
+/// a likely fix for an error, the edit can be applied without asking, fixes that need a choice only come with the message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: std::string::String,
+    pub edit: Option<Edit>,
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// what a value could have been meant as, checked in order
+const BOOLEAN_WORDS: [(&str, &str); 6] = [
+    ("yes", "true"),
+    ("on", "true"),
+    ("y", "true"),
+    ("no", "false"),
+    ("off", "false"),
+    ("n", "false"),
+];
+
+const NULL_WORDS: [&str; 4] = ["null", "none", "nil", "undefined"];
+
+/// the error was found at `span` which ends on `line`, suggestions are only made for `key = value` lines that are simple enough
+/// to tell what was meant, values inside arrays or inline tables are left alone
+pub(crate) fn suggest(kind: &ErrorKind, context: &[Context], line: &str, span: Span) -> Option<Suggestion> {
+    let line_start = span.end.offset.checked_sub(line.chars().take(span.end.column.saturating_sub(1)).map(char::len_utf8).sum())?;
+    let line = Line { text: line, number: span.end.line, start: line_start };
+
+    let indent = line.text.len() - line.text.trim_start().len();
+    let key_end = key_end(line.text, indent)?;
+
+    match (context, separator(line.text, key_end)) {
+        ([Context::Value(_)], Some(equals)) => suggest_value(kind, &line, indent, equals + 1),
+        ([], None) => suggest_separator(&line, key_end),
+        _ => None,
+    }
+}
+
+/// the line an error was found on along with where it starts in the source
+struct Line<'a> {
+    text: &'a str,
+    number: usize,
+    /// byte offset of the line
+    start: usize,
+}
+
+impl Line<'_> {
+    fn position(&self, index: usize) -> Position {
+        Position {
+            line: self.number,
+            column: self.text[..index].chars().count() + 1,
+            offset: self.start + index,
+        }
+    }
+
+    fn edit(&self, range: std::ops::Range<usize>, replacement: impl Into<std::string::String>) -> Edit {
+        Edit {
+            span: Span { start: self.position(range.start), end: self.position(range.end) },
+            replacement: replacement.into(),
+        }
+    }
+
+    /// the line as it reads after `edit`
+    fn fixed(&self, edit: &Edit) -> std::string::String {
+        let mut text = self.text.to_string();
+        text.replace_range(edit.span.start.offset - self.start..edit.span.end.offset - self.start, &edit.replacement);
+        text.trim().to_string()
+    }
+}
+
+/// where the possibly dotted and quoted key starting at `start` ends, `None` if it is not a key at all
+fn key_end(text: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    let mut end = start;
+
+    for (i, c) in text[start..].char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {},
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if BARE_KEY_CHARS.contains(&c) || c == '.' => {},
+            None => break,
+        }
+        end = start + i + c.len_utf8();
+    }
+
+    (quote.is_none() && end > start).then_some(end)
+}
+
+/// the `=` following the key, whitespace around it aside
+fn separator(text: &str, key_end: usize) -> Option<usize> {
+    let rest = &text[key_end..];
+    let trimmed = rest.trim_start();
+
+    trimmed.starts_with('=').then(|| key_end + rest.len() - trimmed.len())
+}
+
+/// the value is a misspelled boolean, a null or bare words that were meant as a string
+fn suggest_value(kind: &ErrorKind, line: &Line<'_>, indent: usize, value_start: usize) -> Option<Suggestion> {
+    // unterminated multi-line strings end up on whatever line the document ends with
+    if matches!(kind, ErrorKind::Format(FormatError::ExpectedSequence(_))) {
+        return None;
+    }
+
+    let rest = &line.text[value_start..];
+    let start = value_start + rest.len() - rest.trim_start().len();
+    let value = line.text[start..].split(COMMENT_START).next().unwrap_or_default().trim_end();
+    let end = start + value.len();
+
+    let first = value.chars().next()?;
+    if ['"', '\'', '[', '{'].contains(&first) {
+        return None;
+    }
+
+    let lower = value.to_lowercase();
+    let boolean = if lower == "true" || lower == "false" {
+        Some((lower.as_str(), "booleans are written in lowercase"))
+    } else if let Some((_, meant)) = BOOLEAN_WORDS.iter().find(|(word, _)| *word == lower) {
+        Some((*meant, "booleans are either `true` or `false`"))
+    } else {
+        ["true", "false"].into_iter()
+            .find(|meant| is_misspelling(&lower, meant))
+            .map(|meant| (meant, "booleans are either `true` or `false`"))
+    };
+
+    if let Some((meant, reason)) = boolean {
+        let edit = line.edit(start..end, meant);
+        return Some(Suggestion { message: format!("{reason}, did you mean `{meant}`?"), edit: Some(edit) });
+    }
+
+    if NULL_WORDS.contains(&lower.as_str()) {
+        // the whole entry goes, comment included
+        let edit = line.edit(indent..line.text.len(), "");
+        let key = line.text[indent..value_start - 1].trim_end();
+        return Some(Suggestion { message: format!("TOML has no null value, leave out `{key}` to have it unset"), edit: Some(edit) });
+    }
+
+    // a number or boolean followed by more words is more likely missing the `#` of a comment
+    let first_word = lower.split_whitespace().next().unwrap_or_default();
+    if !first.is_alphabetic() || first_word == "true" || first_word == "false" {
+        return None;
+    }
+
+    let quoted = format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let message = format!("strings have to be quoted, did you mean `{quoted}`?");
+    Some(Suggestion { message, edit: Some(line.edit(start..end, quoted)) })
+}
+
+/// the key is followed by `:` or directly by the value, or by nothing at all
+fn suggest_separator(line: &Line<'_>, key_end: usize) -> Option<Suggestion> {
+    // whatever went wrong with a line holding a `=` is not a missing one
+    if line.text.split(COMMENT_START).next().unwrap_or_default().contains('=') {
+        return None;
+    }
+
+    let rest = &line.text[key_end..];
+    let trimmed = rest.trim_start();
+
+    let (edit, reason) = if trimmed.starts_with(':') {
+        let colon = key_end + rest.len() - trimmed.len();
+        (line.edit(key_end..colon + 1, " ="), "keys and values are separated by `=`")
+    } else if trimmed.is_empty() || trimmed.starts_with(COMMENT_START) {
+        // there is no telling what the value should be
+        let key = line.text[..key_end].trim_start();
+        return Some(Suggestion { message: format!("every key needs a value, write it after `{key} =`"), edit: None });
+    } else if rest.len() != trimmed.len() {
+        (line.edit(key_end..key_end, " ="), "keys and values are separated by `=`")
+    } else {
+        return None;
+    };
+
+    Some(Suggestion { message: format!("{reason}, did you mean `{}`?", line.fixed(&edit)), edit: Some(edit) })
+}
+
+/// `word` is `meant` with a single typo, or two of them for longer words, a swap of neighbouring letters counting as one
+fn is_misspelling(word: &str, meant: &str) -> bool {
+    let word: Vec<char> = word.chars().collect();
+    let meant: Vec<char> = meant.chars().collect();
+    let allowed = if meant.len() >= 5 { 2 } else { 1 };
+
+    if word.len().abs_diff(meant.len()) > allowed {
+        return false;
+    }
+
+    // optimal string alignment distance, with a row for every prefix of `word`
+    let mut rows = vec![(0..=meant.len()).collect::<Vec<usize>>()];
+    for i in 1..=word.len() {
+        let mut row = vec![i; meant.len() + 1];
+        for j in 1..=meant.len() {
+            let cost = usize::from(word[i - 1] != meant[j - 1]);
+            row[j] = (rows[i - 1][j] + 1).min(row[j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && word[i - 1] == meant[j - 2] && word[i - 2] == meant[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+
+    (1..=allowed).contains(&rows[word.len()][meant.len()])
+}
@@ -5,45 +5,136 @@ mod boolean;
 mod string;
 mod number;
 mod datetime;
+mod array;
+mod table;
 
 use std::collections::HashMap;
 
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::reader::char_supplier::Span;
 
 pub use number::Number;
 pub use boolean::Boolean;
 pub use string::{String, StringType};
 pub use datetime::DateTime;
+pub use array::Array;
+pub use table::InlineTable;
 
 
 #[derive(Debug)]
 pub enum NumberType {
-    Integer(isize),
-    Float(f64)
+    Integer(i64),
+    Float(f64),
+    #[cfg(feature = "big-integer")]
+    BigInteger(num_bigint::BigInt),
 }
 
-impl ToString for NumberType {
-    fn to_string(&self) -> std::string::String {
-        match self {
-            Self::Integer(v) => v.to_string(),
-            Self::Float(v) => v.to_string()
-        }
+impl std::fmt::Display for NumberType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::writer::write_number(f, self)
+    }
+}
+
+/// fractional seconds exactly as written, only the first nine digits make it into the parsed time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fraction {
+    digits: std::string::String,
+}
+
+impl Fraction {
+    /// nanoseconds are the finest precision kept, further digits are truncated as the spec requires
+    pub const MAX_PRECISION: usize = 9;
+
+    pub fn new(digits: std::string::String) -> Self {
+        Fraction { digits }
+    }
+
+    /// the digits after the decimal point, including the ones beyond nanoseconds
+    pub fn digits(&self) -> &str {
+        &self.digits
+    }
+
+    /// number of digits written after the decimal point
+    pub fn precision(&self) -> usize {
+        self.digits.len()
+    }
+
+    /// whether digits beyond nanoseconds were dropped from the parsed time
+    pub fn is_truncated(&self) -> bool {
+        self.digits.len() > Self::MAX_PRECISION
+    }
+
+    pub fn nanos(&self) -> u32 {
+        self.digits.chars()
+            .chain(std::iter::repeat('0'))
+            .take(Self::MAX_PRECISION)
+            .fold(0, |nanos, c| nanos * 10 + c.to_digit(10).expect("fraction holds only digits"))
+    }
+}
+
+impl std::fmt::Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, ".{}", self.digits)
+    }
+}
+
+/// the offset of a date-time exactly as written, `Z`, `z` or `±hh:mm`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offset {
+    written: std::string::String,
+}
+
+impl Offset {
+    pub fn new(written: std::string::String) -> Self {
+        Offset { written }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.written
+    }
+
+    /// `-00:00` is UTC as well but says the local offset is unknown, unlike `+00:00` and `Z`
+    pub fn is_unknown_local(&self) -> bool {
+        self.written == "-00:00"
+    }
+}
+
+impl std::fmt::Display for Offset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.written)
     }
 }
 
 #[derive(Debug)]
 pub enum DateTimeType {
-    Date(NaiveDate),
-    Time(NaiveTime),
-    DateTime(NaiveDateTime),
+    /// `1979-05-27T07:32:00-08:00`, the offset is kept as written
+    OffsetDateTime(chrono::DateTime<FixedOffset>, Option<Fraction>, Offset),
+    /// `1979-05-27T07:32:00`
+    LocalDateTime(NaiveDateTime, Option<Fraction>),
+    /// `1979-05-27`
+    LocalDate(NaiveDate),
+    /// `07:32:00`
+    LocalTime(NaiveTime, Option<Fraction>),
 }
 
-impl ToString for DateTimeType {
-    fn to_string(&self) -> std::string::String {
-        match self {
-            Self::Date(v) => v.to_string(),
-            Self::Time(v) => v.to_string(),
-            Self::DateTime(v) => v.to_string(),
+impl std::fmt::Display for DateTimeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // fractions are written from the source digits so the precision survives a round-trip
+        let (value, fraction, offset) = match self {
+            Self::OffsetDateTime(v, fraction, offset) => (v.format("%Y-%m-%dT%H:%M:%S").to_string(), fraction, Some(offset)),
+            Self::LocalDateTime(v, fraction) => (v.format("%Y-%m-%dT%H:%M:%S").to_string(), fraction, None),
+            Self::LocalDate(v) => (v.format("%Y-%m-%d").to_string(), &None, None),
+            Self::LocalTime(v, fraction) => (v.format("%H:%M:%S").to_string(), fraction, None),
+        };
+
+        write!(f, "{value}")?;
+        if let Some(fraction) = fraction {
+            write!(f, "{fraction}")?;
+        }
+        match offset {
+            Some(offset) => write!(f, "{offset}"),
+            None => Ok(()),
         }
     }
 }
@@ -51,9 +142,10 @@ impl ToString for DateTimeType {
 impl Clone for DateTimeType {
     fn clone(&self) -> Self {
         match self {
-            Self::Date(arg0) => Self::Date(arg0.clone()),
-            Self::Time(arg0) => Self::Time(arg0.clone()),
-            Self::DateTime(arg0) => Self::DateTime(arg0.clone()),
+            Self::OffsetDateTime(arg0, arg1, arg2) => Self::OffsetDateTime(*arg0, arg1.clone(), arg2.clone()),
+            Self::LocalDateTime(arg0, arg1) => Self::LocalDateTime(*arg0, arg1.clone()),
+            Self::LocalDate(arg0) => Self::LocalDate(*arg0),
+            Self::LocalTime(arg0, arg1) => Self::LocalTime(*arg0, arg1.clone()),
         }
     }
 }
@@ -64,18 +156,17 @@ pub enum Value {
     Boolean(bool),
     String(std::string::String),
     DateTime(DateTimeType),
-    Nested(Box<HashMap<Key, Value>>)
+    Array(Vec<Value>),
+    Nested(Table),
+    ArrayOfTables(Vec<Table>),
 }
 
+pub type Table = HashMap<Key, Value>;
+
+/// values are displayed the way they would be written after `=` in a TOML document
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::String(v) => write!(f, "{}", v),
-            Self::Boolean(v) =>  write!(f, "{}", v.to_string()),
-            Self::Number(v) =>  write!(f, "{}", v.to_string()),
-            Self::DateTime(v) =>  write!(f, "{}", v.to_string()),
-            Self::Nested(v) =>  write!(f, "{:?}", v),
-        }
+        crate::writer::write_value(f, self)
     }
 }
 
@@ -84,10 +175,14 @@ impl Clone for Value {
         match self {
             Self::Number(NumberType::Float(arg0)) => Self::Number(NumberType::Float(*arg0)),
             Self::Number(NumberType::Integer(arg0)) => Self::Number(NumberType::Integer(*arg0)),
-            Self::Boolean(arg0) => Self::Boolean(arg0.clone()),
+            #[cfg(feature = "big-integer")]
+            Self::Number(NumberType::BigInteger(arg0)) => Self::Number(NumberType::BigInteger(arg0.clone())),
+            Self::Boolean(arg0) => Self::Boolean(*arg0),
             Self::String(arg0) => Self::String(arg0.clone()),
             Self::DateTime(arg0) => Self::DateTime(arg0.clone()),
+            Self::Array(arg0) => Self::Array(arg0.clone()),
             Self::Nested(arg0) => Self::Nested(arg0.clone()),
+            Self::ArrayOfTables(arg0) => Self::ArrayOfTables(arg0.clone()),
         }
     }
 }
@@ -104,6 +199,18 @@ impl Key {
             name
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// joins a key path back into its dotted form
+    pub fn join(path: &[Key]) -> std::string::String {
+        path.iter()
+            .map(|key| key.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(".")
+    }
 }
 
 impl std::fmt::Display for Key {
@@ -118,32 +225,51 @@ impl Clone for Key {
     }
 }
 
-#[derive(Debug)]
-pub struct Entry {
-    key: Key,
-    value: Value,
+/// one step from a table into a key or from an array into an element
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+pub enum Segment {
+    Key(Key),
+    Index(usize),
 }
 
-impl std::fmt::Display for Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}={:?}", self.key, v)
-    }
-}
-
-impl Entry {
-    pub fn new(key: Key, value: Value) -> Self {
-        Self {
-            key,
-            value
+impl Segment {
+    /// writes `path` the way it is shown in messages, e.g. `servers[1]."host name"`
+    pub fn join(path: &[Segment]) -> std::string::String {
+        let mut joined = std::string::String::new();
+        for segment in path {
+            match segment {
+                Segment::Key(key) => {
+                    if !joined.is_empty() {
+                        joined.push('.');
+                    }
+                    crate::writer::write_key(&mut joined, key).expect("writing into a string does not fail");
+                },
+                Segment::Index(i) => joined.push_str(&format!("[{i}]")),
+            }
         }
+        joined
     }
+}
 
-    pub fn get_key(&self) -> &Key {
-        &self.key
-    }
+/// where an item of the document was written, parts that do not apply to the item are left out
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Location {
+    /// the key as written, dotted keys cover the segments up to the item, e.g. `a.b` of `a.b.c = 1` for the table `a.b`
+    pub key: Option<Span>,
+    /// the value after `=`, or the element itself for elements of arrays
+    pub value: Option<Span>,
+    /// the whole `[header]` or `[[header]]` for tables defined by one
+    pub header: Option<Span>,
+}
 
-    pub fn get_value(&self) -> &Value {
-        &self.value
+impl Location {
+    /// fills in the parts still unknown from `other`, the first place an item was mentioned at wins
+    pub(crate) fn merge(&mut self, other: Location) {
+        self.key = self.key.or(other.key);
+        self.value = self.value.or(other.value);
+        self.header = self.header.or(other.header);
     }
 }
 
+/// where every key, value, table and array element of a document was written, looked up by its path from the root
+pub type Spans = HashMap<Vec<Segment>, Location>;
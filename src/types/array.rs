@@ -0,0 +1,49 @@
+use crate::errors::{Context, FormatError, ParserError};
+use crate::parsers::{ValueParser, ValueSpans};
+use crate::reader::char_supplier::Supplier;
+use crate::skip_blanks;
+use crate::types::{Segment, Value};
+
+pub struct Array;
+
+impl super::TypeParser<(Vec<Value>, ValueSpans)> for Array {
+    /// `first` is the opening bracket, elements may be spread across lines and separated by comments
+    fn parse(_first: char, input: &mut impl Supplier) -> Result<(Vec<Value>, ValueSpans), crate::errors::ParserError> {
+        let mut values = Vec::new();
+        let mut spans = ValueSpans::new();
+
+        let next = input.get();
+        let mut c = skip_blanks(input, next)?;
+
+        loop {
+            let first = match c {
+                Some(']') => break,
+                Some(_c) => _c,
+                None => return ParserError::from(FormatError::ExpectedCharacter(']')),
+            };
+
+            let i = values.len();
+            let (value, inner) = ValueParser::parse_value(first, input)
+                .map_err(|err| err.under(Segment::Index(i)).within(Context::ArrayElement(Vec::new(), i)))?;
+            spans.extend(inner.into_iter().map(|(mut path, location)| {
+                path.insert(0, Segment::Index(i));
+                (path, location)
+            }));
+            values.push(value);
+
+            c = match skip_blanks(input, input.last())? {
+                Some(',') => {
+                    let next = input.get();
+                    skip_blanks(input, next)?
+                },
+                Some(']') => break,
+                Some(_) => return ParserError::from(FormatError::ExpectedCharacter(',')),
+                None => return ParserError::from(FormatError::ExpectedCharacter(']')),
+            };
+        }
+
+        input.get();
+
+        Ok((values, spans))
+    }
+}
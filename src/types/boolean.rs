@@ -7,26 +7,22 @@ pub struct Boolean;
 impl super::TypeParser<bool> for Boolean {
     fn parse(first: char, input: &mut impl Supplier) -> Result<bool, crate::errors::ParserError> {
         let mut value = String::from(first);
-        loop {
-            let c = if let Some(_c) = input.get() {
-                if _c.is_linebreak() || _c.is_whitespace() || _c.is_comment_start() {
-                    break;
-                }
-                _c
-            } else {
+        while let Some(c) = input.get() {
+            if c.is_value_end() {
                 break;
-            };
-            
+            }
+
             if !['t','r','u','e','f','a','l','s'].contains(&c) {
                 return ParserError::from(FormatError::UnallowedCharacter(c, InTypeBoolean))
             }
 
             value.push(c);
-        };
+        }
 
-        match value.parse::<bool>() {
-            Ok(v) => Ok(v),
-            Err(err) => ParserError::from(err),
+        match value.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => ParserError::from(FormatError::MalformedBoolean(value)),
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use chrono::{FixedOffset, NaiveDate, NaiveTime};
 
-use crate::{errors::{FormatError, ParserError, UnallowedCharacterReason}, reader::char_supplier::{Supplier, ToSupplier}, types::DateTimeType, CharExt};
+use crate::{errors::{FormatError, ParserError, UnallowedCharacterReason}, reader::char_supplier::{Supplier, ToSupplier}, types::{DateTimeType, Fraction, Offset}, CharExt};
 
 pub struct DateTime;
 
@@ -8,10 +8,10 @@ fn read_digits<T: std::str::FromStr>(input: &mut impl Supplier, mut len: u8, suf
     let mut buf = String::new();
 
     while let Some(c) = input.get() {
-        if suffixes.contains(&c) || (expect_end_of_line && (c.is_whitespace() || c.is_comment_start())) {
+        if suffixes.contains(&c) || (expect_end_of_line && c.is_value_end()) {
             break;
         }
-        if len == 0 || !c.is_digit(10) {
+        if len == 0 || !c.is_ascii_digit() {
             return None;
         }
 
@@ -23,10 +23,7 @@ fn read_digits<T: std::str::FromStr>(input: &mut impl Supplier, mut len: u8, suf
         return None;
     }
 
-    match buf.parse::<T>() {
-        Ok(c) => Some(c),
-        _ => None
-    }
+    buf.parse::<T>().ok()
 }
 
 impl DateTime {
@@ -67,7 +64,7 @@ impl DateTime {
             } else if c == ':' {
                 is_time = true;
                 break;
-            } else if !c.is_digit(10) {
+            } else if !c.is_ascii_digit() {
                 return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeDateTime));
             }
         
@@ -88,7 +85,7 @@ impl DateTime {
                 return ParserError::from(FormatError::ExpectedSequence("MM".to_string()))
             };
         
-            let day: u32 = if let Some(_day) = read_digits(input, 2, &['T'], true) {
+            let day: u32 = if let Some(_day) = read_digits(input, 2, &['T', 't'], true) {
                 _day
             } else {
                 return ParserError::from(FormatError::ExpectedSequence("DD".to_string()))
@@ -96,36 +93,43 @@ impl DateTime {
         
             _buf.clear();
         
-            is_time = input.last() == Some('T');
+            is_time = matches!(input.last(), Some('T' | 't'));
         
-            NaiveDate::from_ymd_opt(year, month, day)
+            match NaiveDate::from_ymd_opt(year, month, day) {
+                Some(_date) => Some(_date),
+                None => return ParserError::from(FormatError::InvalidDateTime(format!("{year:04}-{month:02}-{day:02}"))),
+            }
         } else {
             None
         };
         
         if !is_time && date.is_some() && input.last() == Some(' ') {
             if let Some(c) = input.get() {
-                if c.is_digit(10) {
+                if c.is_ascii_digit() {
                     _buf.push(c)
-                } else if !c.is_whitespace() && !c.is_comment_start() {
+                } else if !c.is_value_end() {
                     return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeDate))
                 }
             };
         
-            is_time = if _buf.len() == 0 {
+            is_time = if _buf.is_empty() {
                 false
             } else {
                 loop {
                     if _buf.len() > 2 {
                         return ParserError::from(FormatError::ExpectedSequence("HH".to_string()))
                     } else if let Some(c) = input.get() {
-                        if c.is_digit(10) {
+                        if c.is_ascii_digit() {
                             _buf.push(c);
                         } else if c == ':' {
                             break true;
+                        } else if c.is_value_end() {
+                            return ParserError::from(FormatError::ExpectedSequence("hh:".to_string()))
                         } else {
                             return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeTime))
                         }
+                    } else {
+                        return ParserError::from(FormatError::ExpectedSequence("hh:".to_string()))
                     }
                 }
             }
@@ -148,78 +152,83 @@ impl DateTime {
                 return ParserError::from(FormatError::ExpectedSequence("mm:".to_string()));
             };
         
-            let nanos: u32 = if let Some(_second) = read_digits::<u32>(input, 2, &['Z', '-', '+', '.'], true) {
-                let mut sec = _second * 1_000_000_000;
-
-                if input.last() == Some('.') {
-                    _buf.push('.');
-                    loop {
-                        if let Some(c) = input.get() {
-                            if c.is_digit(10) {
-                                _buf.push(c);
-                            } else if c.is_whitespace() || c.is_comment_start() || ['Z', '-', '+'].contains(&c) {
-                                break;
-                            } else {
-                                return ParserError::from(FormatError::ExpectedSequence(".ffffff".to_string()));
-                            }
-                        }
+            let second: u32 = if let Some(_second) = read_digits(input, 2, &['Z', 'z', '-', '+', '.'], true) {
+                _second
+            } else {
+                return ParserError::from(FormatError::ExpectedSequence("ss".to_string()));
+            };
+
+            let mut fraction = None;
+            if input.last() == Some('.') {
+                while let Some(c) = input.get() {
+                    if c.is_ascii_digit() {
+                        _buf.push(c);
+                    } else if c.is_value_end() || ['Z', 'z', '-', '+'].contains(&c) {
+                        break;
+                    } else {
+                        return ParserError::from(FormatError::ExpectedSequence(".ffffff".to_string()));
                     }
+                }
 
-                    let frac = _buf.parse::<f32>()?;
-                    sec += (frac * 1_000_000_000.0) as u32;
+                if _buf.is_empty() {
+                    return ParserError::from(FormatError::ExpectedSequence(".ffffff".to_string()));
                 }
+                fraction = Some(Fraction::new(std::mem::take(&mut _buf)));
+            }
+            let nanos = fraction.as_ref().map_or(0, Fraction::nanos);
 
-                sec
-            } else {
-                return ParserError::from(FormatError::ExpectedSequence("ss".to_string()));
+            let _time = match NaiveTime::from_hms_nano_opt(hour, minute, second, nanos) {
+                Some(_time) => _time,
+                None => return ParserError::from(FormatError::InvalidDateTime(format!("{hour:02}:{minute:02}:{second:02}"))),
             };
-        
-            let _time = NaiveTime::from_hms_nano_opt(hour, minute, 0, nanos);
-        
+
             let offset = match input.last() {
-                Some(_c) => match _c {
-                    '-' | '+' => {
-                        let shift_hour: i32 = if let Some(_hour) = read_digits(input, 2, &[':'], false) {
-                            _hour
-                        } else {
-                            return ParserError::from(FormatError::ExpectedSequence("HH".to_string()));
-                        };
-        
-                        let shift_minute: i32 = if let Some(_minute) = read_digits(input, 2, &[], true) {
-                            _minute
-                        } else {
-                            return ParserError::from(FormatError::ExpectedSequence("mm".to_string()));
-                        };
-        
-                        let mut shift = (shift_hour * 60 + shift_minute) * 60;
-                        if _c == '-' {
-                            shift *= -1;
-                        }
-        
-                        FixedOffset::east_opt(shift)
-                    },
-                    'Z' => FixedOffset::east_opt(0),
-                    _ => None
+                Some(_c @ ('-' | '+')) => {
+                    let shift_hour: i32 = if let Some(_hour) = read_digits(input, 2, &[':'], false) {
+                        _hour
+                    } else {
+                        return ParserError::from(FormatError::ExpectedSequence("HH".to_string()));
+                    };
+
+                    let shift_minute: i32 = if let Some(_minute) = read_digits(input, 2, &[], true) {
+                        _minute
+                    } else {
+                        return ParserError::from(FormatError::ExpectedSequence("mm".to_string()));
+                    };
+
+                    let mut shift = (shift_hour * 60 + shift_minute) * 60;
+                    if _c == '-' {
+                        shift *= -1;
+                    }
+
+                    match FixedOffset::east_opt(shift).filter(|_| shift_hour < 24 && shift_minute < 60) {
+                        Some(offset) => Some((_c, offset, Offset::new(format!("{_c}{shift_hour:02}:{shift_minute:02}")))),
+                        None => return ParserError::from(FormatError::InvalidDateTime(format!("{_c}{shift_hour:02}:{shift_minute:02}"))),
+                    }
                 },
-                None => None,
+                Some(_c @ ('Z' | 'z')) => {
+                    input.get();
+                    Some((_c, FixedOffset::east_opt(0).expect("zero offset is always valid"), Offset::new(_c.to_string())))
+                },
+                _ => None,
             };
-        
-            (_time, offset)
+
+            (Some((_time, fraction)), offset)
         } else {
             (None, None)
         };
-        
-        return match (date, time) {
-            (Some(date), (None, None)) => Ok(DateTimeType::Date(date)),
-            (None, (Some(time), None)) => Ok(DateTimeType::Time(time)),
-            (Some(date), (Some(time), None)) => Ok(DateTimeType::DateTime(date.and_time(time))),
-            (Some(date), (Some(time), Some(offset))) => match date.and_time(time).checked_add_offset(offset) {
-                Some(datetime) => Ok(DateTimeType::DateTime(datetime)),
-                None => ParserError::from(FormatError::Unknown(format!("failed to contruct datetime from {date} {time} {offset}")))
-            }
-            _ => ParserError::from(FormatError::EmptyValue),
-        };
 
+        match (date, time) {
+            (Some(date), (None, _)) => Ok(DateTimeType::LocalDate(date)),
+            (None, (Some((time, fraction)), None)) => Ok(DateTimeType::LocalTime(time, fraction)),
+            (None, (Some(_), Some((c, _, _)))) => ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeTime)),
+            (Some(date), (Some((time, fraction)), None)) => Ok(DateTimeType::LocalDateTime(date.and_time(time), fraction)),
+            (Some(date), (Some((time, fraction)), Some((_, offset, written)))) => match date.and_time(time).and_local_timezone(offset).single() {
+                Some(datetime) => Ok(DateTimeType::OffsetDateTime(datetime, fraction, written)),
+                None => ParserError::from(FormatError::InvalidDateTime(format!("{date}T{time}{written}"))),
+            },
+            (None, (None, _)) => ParserError::from(FormatError::EmptyValue),
+        }
     }
 }
 
@@ -228,7 +237,7 @@ impl super::TypeParser<DateTimeType> for DateTime {
         let first_as_string = first.to_string();
         let mut buf = ToSupplier::from_string(&first_as_string);
 
-        return Self::parse_with_buf(&mut buf, input)
+        Self::parse_with_buf(&mut buf, input)
     }
 
 }
\ No newline at end of file
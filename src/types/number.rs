@@ -1,15 +1,15 @@
-use crate::errors::{FormatError, ParserError, UnallowedCharacterReason};
+use std::num::IntErrorKind;
+
+use crate::errors::{FormatError, NumberReason, ParserError, UnallowedCharacterReason, UnderscoreReason};
 use crate::reader::char_supplier::ToSupplier;
-use crate::{reader::char_supplier::Supplier, types::NumberType, CharExt as _};
+use crate::{reader::char_supplier::Supplier, types::NumberType, CharExt as _, RADIX_PREFIXES};
 
 pub struct Number;
 
 impl Number {
-    pub fn parse_with_buf(buf: &mut impl Supplier, input: &mut impl Supplier) -> Result<NumberType, crate::errors::ParserError> {
-        let mut dotted = false;
-        let mut signed = false;
-
-        let mut value = String::new();
+    /// reads characters up to the end of the value, first from `buf` and then from `input`
+    fn read_literal(buf: &mut impl Supplier, input: &mut impl Supplier) -> String {
+        let mut literal = String::new();
         let mut from_buf = true;
 
         loop {
@@ -25,35 +25,167 @@ impl Number {
                 input.get()
             };
 
-            let c = if let Some(_c) = next {
-                if _c.is_linebreak() || _c.is_whitespace() || _c.is_comment_start() {
-                    break;
-                }
-                _c
-            } else {
-                break;
+            match next {
+                Some(c) if !c.is_value_end() => literal.push(c),
+                _ => break literal,
+            }
+        }
+    }
+
+    /// checks that every underscore of `digits` sits between two digits and returns them without underscores
+    fn strip_underscores(digits: &str, radix: u32) -> Result<String, UnderscoreReason> {
+        let chars: Vec<char> = digits.chars().collect();
+
+        for (i, c) in chars.iter().enumerate() {
+            if *c != '_' {
+                continue;
+            }
+
+            let previous = if i > 0 { Some(chars[i - 1]) } else { None };
+            let reason = match (previous, chars.get(i + 1)) {
+                (None | Some('+' | '-'), _) => UnderscoreReason::Leading,
+                (_, None) => UnderscoreReason::Trailing,
+                (_, Some('_')) => UnderscoreReason::Repeated,
+                (Some(previous), Some(next)) if previous.is_digit(radix) && next.is_digit(radix) => continue,
+                _ => UnderscoreReason::NotBetweenDigits,
             };
 
-            if c == '.' && !dotted {
-                dotted = true;
-            } else if !c.is_digit(10) && !(['+', '-'].contains(&c) && !signed) {
-                return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeNumber));
+            return Err(reason);
+        }
+
+        Ok(digits.replace('_', ""))
+    }
+
+    /// `digits` is whatever follows the `0x`, `0o` or `0b` prefix of `literal`
+    fn parse_radix(literal: &str, digits: &str, radix: u32) -> Result<NumberType, crate::errors::ParserError> {
+        if let Some(c) = digits.chars().find(|c| !c.is_digit(radix) && *c != '_') {
+            return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeRadixInteger));
+        }
+
+        let digits = match Self::strip_underscores(digits, radix) {
+            Ok(digits) => digits,
+            Err(reason) => return ParserError::from(FormatError::MisplacedUnderscore(literal.to_string(), reason)),
+        };
+
+        if digits.is_empty() {
+            return ParserError::from(FormatError::EmptyValue);
+        }
+
+        Self::to_integer(literal, &digits, radix)
+    }
+
+    /// converts validated `digits` into a 64-bit integer, `literal` is what gets reported if it does not fit
+    fn to_integer(literal: &str, digits: &str, radix: u32) -> Result<NumberType, crate::errors::ParserError> {
+        match i64::from_str_radix(digits, radix) {
+            Ok(v) => Ok(NumberType::Integer(v)),
+            Err(err) if matches!(err.kind(), IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => Self::overflow(literal, digits, radix),
+            Err(err) => ParserError::from(err),
+        }
+    }
+
+    #[cfg(not(feature = "big-integer"))]
+    fn overflow(literal: &str, _digits: &str, _radix: u32) -> Result<NumberType, crate::errors::ParserError> {
+        ParserError::from(FormatError::IntegerOverflow(literal.to_string()))
+    }
+
+    /// with `big-integer` enabled integers out of the 64-bit range are kept as they are instead of being rejected
+    #[cfg(feature = "big-integer")]
+    fn overflow(literal: &str, digits: &str, radix: u32) -> Result<NumberType, crate::errors::ParserError> {
+        match num_bigint::BigInt::parse_bytes(digits.as_bytes(), radix) {
+            Some(v) => Ok(NumberType::BigInteger(v)),
+            None => ParserError::from(FormatError::IntegerOverflow(literal.to_string())),
+        }
+    }
+
+    /// checks an unsigned decimal without underscores against `int [ "." digits ] [ ("e" | "E") [ sign ] digits ]`
+    /// where `int` has no leading zeros, returns whether the number is a float
+    fn check_decimal(unsigned: &str) -> Result<bool, FormatError> {
+        fn digits(s: &str) -> (&str, &str) {
+            let len = s.chars().take_while(char::is_ascii_digit).count();
+            s.split_at(len)
+        }
+
+        let malformed = |reason| Err(FormatError::MalformedNumber(unsigned.to_string(), reason));
+
+        let (int, mut rest) = digits(unsigned);
+        let mut is_float = false;
+
+        if int.is_empty() {
+            return malformed(NumberReason::MissingIntegerPart);
+        } else if int.len() > 1 && int.starts_with('0') {
+            return malformed(NumberReason::LeadingZero);
+        }
+
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let (frac, after_frac) = digits(after_dot);
+            if frac.is_empty() {
+                return malformed(NumberReason::MissingFractionalPart);
+            }
+
+            rest = after_frac;
+            is_float = true;
+        }
+
+        if let Some(after_e) = rest.strip_prefix(['e', 'E']) {
+            let (exp, after_exp) = digits(after_e.strip_prefix(['+', '-']).unwrap_or(after_e));
+            if exp.is_empty() {
+                return malformed(NumberReason::MissingExponent);
+            }
+
+            rest = after_exp;
+            is_float = true;
+        }
+
+        match rest.chars().next() {
+            Some(c) => Err(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeNumber)),
+            None => Ok(is_float),
+        }
+    }
+
+    pub fn parse_with_buf(buf: &mut impl Supplier, input: &mut impl Supplier) -> Result<NumberType, crate::errors::ParserError> {
+        let literal = Self::read_literal(buf, input);
+
+        let sign = literal.chars().next().filter(|c| ['+', '-'].contains(c));
+        let unsigned = &literal[sign.map_or(0, char::len_utf8)..];
+
+        if let Some((prefix, radix)) = RADIX_PREFIXES.iter().find(|(prefix, _)| unsigned.starts_with(prefix)) {
+            if let Some(sign) = sign {
+                return ParserError::from(FormatError::UnallowedCharacter(sign, UnallowedCharacterReason::InTypeRadixInteger));
             }
-            
-            signed = true;
-            value.push(c);
+
+            return Self::parse_radix(&literal, &unsigned[prefix.len()..], *radix);
         }
 
-        if dotted {
-            return match value.parse::<f64>() {
+        match unsigned {
+            "inf" if sign == Some('-') => return Ok(NumberType::Float(f64::NEG_INFINITY)),
+            "inf" => return Ok(NumberType::Float(f64::INFINITY)),
+            "nan" if sign == Some('-') => return Ok(NumberType::Float(-f64::NAN)),
+            "nan" => return Ok(NumberType::Float(f64::NAN)),
+            _ => {},
+        }
+
+        if let Some(c) = unsigned.chars().find(|c| !c.is_ascii_digit() && !['_', '.', 'e', 'E', '+', '-'].contains(c)) {
+            return ParserError::from(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InTypeNumber));
+        }
+
+        let stripped = match Self::strip_underscores(&literal, 10) {
+            Ok(digits) => digits,
+            Err(reason) => return ParserError::from(FormatError::MisplacedUnderscore(literal, reason)),
+        };
+
+        let is_float = match Self::check_decimal(&stripped[sign.map_or(0, char::len_utf8)..]) {
+            Ok(is_float) => is_float,
+            Err(FormatError::MalformedNumber(_, reason)) => return ParserError::from(FormatError::MalformedNumber(literal, reason)),
+            Err(err) => return ParserError::from(err),
+        };
+
+        if is_float {
+            match stripped.parse::<f64>() {
                 Ok(v) => Ok(NumberType::Float(v)),
                 Err(err) => ParserError::from(err),
             }
         } else {
-            return match value.parse::<isize>() {
-                Ok(v) => Ok(NumberType::Integer(v)),
-                Err(err) => ParserError::from(err),
-            }
+            Self::to_integer(&literal, &stripped, 10)
         }
     }
 }
@@ -63,6 +195,6 @@ impl super::TypeParser<NumberType> for Number {
         let first_as_string = first.to_string();
         let mut buf = ToSupplier::from_string(&first_as_string);
 
-        return Self::parse_with_buf(&mut buf, input)
+        Self::parse_with_buf(&mut buf, input)
     }
 }
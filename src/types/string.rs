@@ -70,7 +70,7 @@ fn read_escape_seq(input: &mut impl Supplier, is_multiline: bool) -> Result<char
                     None => Err(FormatError::UnknownEscapeSequence)
                 }
             },
-            _ => if !c.is_digit(16) {
+            _ => if !c.is_ascii_hexdigit() {
                 return Err(FormatError::UnallowedCharacter(c, UnallowedCharacterReason::InUnicodeSequence))
             }
         }
@@ -107,7 +107,7 @@ impl StringType {
         self.quote() == *c
     }
 
-    fn to_multiline(self) -> Self {
+    fn into_multiline(self) -> Self {
         match self {
             StringType::Basic => StringType::BasicMultiline,
             StringType::Literal => StringType::LiteralMultiline,
@@ -303,6 +303,7 @@ impl StringType {
 }
 
 impl super::TypeParser<std::string::String> for String {
+    /// `first` is the opening quote, after that `input.last()` holds the first character following the string
     fn parse(first: char, input: &mut impl Supplier) -> Result<std::string::String, crate::errors::ParserError> {
         let mut string_type = if StringType::Basic.is_type_quote(&first) {
             StringType::Basic
@@ -312,14 +313,14 @@ impl super::TypeParser<std::string::String> for String {
         
         let mut quotes: u8 = 0b1;
         let (is_empty_string, first) = loop {
-            let c = if let Some(_c) = input.get() {
-                _c
-            } else {
-                return ParserError::from(FormatError::EmptyValue)
+            let c = match input.get() {
+                Some(_c) => _c,
+                None if quotes == 0b010 => return Ok(std::string::String::new()),
+                None => return ParserError::from(FormatError::EmptyValue),
             };
             
             if quotes == 0b100 {
-                string_type = string_type.to_multiline();
+                string_type = string_type.into_multiline();
                 break (false, c);
             } else if string_type.is_type_quote(&c) {
                 quotes <<= 0b1;
@@ -330,10 +331,13 @@ impl super::TypeParser<std::string::String> for String {
             }
         };
 
-        return if is_empty_string {
+        // an empty string has already read the character behind it, any other string stops at its closing quote
+        if is_empty_string {
             Ok(std::string::String::new())
         } else {
-            string_type.parse(first, input)
-        };
+            let value = string_type.parse(first, input)?;
+            input.get();
+            Ok(value)
+        }
     }
 }
\ No newline at end of file
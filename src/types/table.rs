@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::errors::{Context, FormatError, ParserError, UnallowedCharacterReason};
+use crate::parsers::{key_spans, KeyParser, ValueParser, ValueSpans};
+use crate::reader::char_supplier::{Position, Supplier};
+use crate::types::{Key, Segment, Table, Value};
+use crate::{skip_whitespaces, CharExt as _};
+
+pub struct InlineTable;
+
+/// where keys of an inline table were defined, tables made by its own dotted keys are kept apart as only those can be entered again
+struct Definitions {
+    dotted: HashMap<Vec<Key>, Position>,
+    keys: HashMap<Vec<Key>, Position>,
+}
+
+impl Definitions {
+    fn defined_at(&self, path: &[Key]) -> Position {
+        self.dotted.get(path)
+            .or_else(|| self.keys.get(path))
+            .copied()
+            .expect("every key of the table is recorded")
+    }
+}
+
+impl InlineTable {
+    /// walks down `path` inside the inline table, only tables created by dotted keys of this very table can be entered
+    fn table_mut<'a>(root: &'a mut Table, definitions: &mut Definitions, path: &[Key], position: Position) -> Result<&'a mut Table, ParserError> {
+        let mut table = root;
+
+        for i in 0..path.len() {
+            let key = &path[i];
+            let is_new = !table.contains_key(key);
+
+            if is_new {
+                definitions.dotted.insert(path[..=i].to_vec(), position);
+            } else if !definitions.dotted.contains_key(&path[..=i]) {
+                let first = definitions.defined_at(&path[..=i]);
+
+                return match table.get(key) {
+                    Some(Value::Nested(_)) => ParserError::from(FormatError::ExtendedInlineTable(Key::join(&path[..=i]), first, position)),
+                    _ => ParserError::from(FormatError::DuplicateKey(Key::join(&path[..=i]), first, position)),
+                };
+            }
+
+            table = match table.entry(key.clone()).or_insert_with(|| Value::Nested(Table::new())) {
+                Value::Nested(inner) => inner,
+                _ => unreachable!("only tables are created by dotted keys"),
+            };
+        }
+
+        Ok(table)
+    }
+}
+
+impl super::TypeParser<(Table, ValueSpans)> for InlineTable {
+    /// `first` is the opening brace, the whole table has to fit on a single line
+    fn parse(_first: char, input: &mut impl Supplier) -> Result<(Table, ValueSpans), crate::errors::ParserError> {
+        Self::parse_entries(input).map_err(|err| err.within(Context::InlineTable(Vec::new())))
+    }
+}
+
+impl InlineTable {
+    fn parse_entries(input: &mut impl Supplier) -> Result<(Table, ValueSpans), ParserError> {
+        let mut table = Table::new();
+        let mut spans = ValueSpans::new();
+        let mut definitions = Definitions {
+            dotted: HashMap::new(),
+            keys: HashMap::new(),
+        };
+
+        let mut c = skip_whitespaces(input, true);
+        if c == Some('}') {
+            input.get();
+            return Ok((table, spans));
+        }
+
+        loop {
+            let first = match c {
+                Some('}') => return ParserError::from(FormatError::UnallowedCharacter(',', UnallowedCharacterReason::InInlineTable)),
+                Some(_c) => _c,
+                None => return ParserError::from(FormatError::ExpectedCharacter('}')),
+            };
+            let position = input.position();
+
+            let spanned_path = KeyParser::parse_spanned_path(Some(first), input, '=')?;
+            let path: Vec<Key> = spanned_path.iter().map(|(key, _)| key.clone()).collect();
+            let segments: Vec<Segment> = path.iter().cloned().map(Segment::Key).collect();
+
+            let (value, inner_spans) = match skip_whitespaces(input, true) {
+                Some(_c) => ValueParser::parse_value(_c, input).map_err(|err| err.under_path(&segments))?,
+                None => return ParserError::from(FormatError::EmptyValue).map_err(|err: ParserError| err.under_path(&segments)),
+            };
+
+            let (key, parents) = path.split_last().expect("key path is never empty");
+            let inner = Self::table_mut(&mut table, &mut definitions, parents, position).map_err(|err| err.under_path(&segments))?;
+
+            if inner.contains_key(key) {
+                let err = FormatError::DuplicateKey(Key::join(&path), definitions.defined_at(&path), position);
+                return ParserError::from(err).map_err(|err: ParserError| err.under_path(&segments));
+            }
+            inner.insert(key.clone(), value);
+            definitions.keys.insert(path, position);
+
+            spans.extend(key_spans(&spanned_path));
+            spans.extend(inner_spans.into_iter().map(|(relative, location)| {
+                (segments.iter().cloned().chain(relative).collect(), location)
+            }));
+
+            let next = match input.last() {
+                Some(_c) if _c.is_whitespace() && !_c.is_linebreak() => skip_whitespaces(input, true),
+                last => last,
+            };
+
+            c = match next {
+                Some(',') => skip_whitespaces(input, true),
+                Some('}') => break,
+                _ => return ParserError::from(FormatError::ExpectedCharacter('}')),
+            };
+        }
+
+        input.get();
+
+        Ok((table, spans))
+    }
+}
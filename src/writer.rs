@@ -0,0 +1,215 @@
+use std::fmt::Write;
+
+use crate::{types::{Key, NumberType, Table, Value}, CharExt, BARE_KEY_CHARS, DOUBLE_QUOTE, ESCAPE_SEQUENCE_TO_CHAR};
+
+/// how a table is introduced in the output
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Header {
+    /// the root table, its keys come first and need no header
+    Root,
+    /// `[a.b]`, left out when the table has nothing but sub-tables
+    Table,
+    /// `[[a.b]]`, always written since every header appends an element
+    ArrayElement,
+}
+
+/// writes `root` as a TOML document, keys are sorted so the same tree always gives the same text,
+/// `pretty` puts every element of an array on its own line
+pub fn write_document(out: &mut impl Write, root: &Table, pretty: bool) -> std::fmt::Result {
+    let mut path = Vec::new();
+    write_section(out, &mut path, root, Header::Root, pretty, &mut true)
+}
+
+/// tables are written under their own header unless they can be folded into a dotted key
+fn is_section(value: &Value) -> bool {
+    match value {
+        Value::Nested(_) => !is_dotted(value),
+        Value::ArrayOfTables(tables) => !tables.is_empty(),
+        _ => false,
+    }
+}
+
+/// a table holding a single key which is not a table by itself reads better as `a.b = 1`
+fn is_dotted(value: &Value) -> bool {
+    match value {
+        Value::Nested(table) if table.len() == 1 => table.values().all(|inner| !is_section(inner)),
+        _ => false,
+    }
+}
+
+fn sorted(table: &Table) -> Vec<(&Key, &Value)> {
+    let mut entries: Vec<(&Key, &Value)> = table.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.name().cmp(b.name()));
+    entries
+}
+
+fn write_section<'a>(out: &mut impl Write, path: &mut Vec<&'a Key>, table: &'a Table, header: Header, pretty: bool, is_first: &mut bool) -> std::fmt::Result {
+    let (sections, body): (Vec<_>, Vec<_>) = sorted(table).into_iter().partition(|(_, value)| is_section(value));
+
+    let needs_header = match header {
+        Header::Root => false,
+        Header::Table => !body.is_empty() || sections.is_empty(),
+        Header::ArrayElement => true,
+    };
+
+    if needs_header {
+        if !*is_first {
+            writeln!(out)?;
+        }
+
+        let (open, close) = if header == Header::ArrayElement { ("[[", "]]") } else { ("[", "]") };
+        write!(out, "{open}")?;
+        write_path(out, path)?;
+        writeln!(out, "{close}")?;
+    }
+
+    for (key, value) in body {
+        write_entry(out, key, value, pretty)?;
+        writeln!(out)?;
+        *is_first = false;
+    }
+    if needs_header {
+        *is_first = false;
+    }
+
+    for (key, value) in sections {
+        path.push(key);
+        match value {
+            Value::Nested(inner) => write_section(out, path, inner, Header::Table, pretty, is_first)?,
+            Value::ArrayOfTables(tables) => for inner in tables {
+                write_section(out, path, inner, Header::ArrayElement, pretty, is_first)?;
+            },
+            _ => unreachable!("only tables are written as sections"),
+        }
+        path.pop();
+    }
+
+    Ok(())
+}
+
+fn write_path(out: &mut impl Write, path: &[&Key]) -> std::fmt::Result {
+    for (i, key) in path.iter().enumerate() {
+        if i > 0 {
+            write!(out, ".")?;
+        }
+        write_key(out, key)?;
+    }
+    Ok(())
+}
+
+/// writes `key = value`, tables that hold a single key are folded into a dotted key on the way
+fn write_entry(out: &mut impl Write, key: &Key, value: &Value, pretty: bool) -> std::fmt::Result {
+    write_key(out, key)?;
+
+    let mut value = value;
+    while is_dotted(value) {
+        let Value::Nested(table) = value else { unreachable!() };
+        let (key, inner) = table.iter().next().expect("dotted tables hold exactly one key");
+
+        write!(out, ".")?;
+        write_key(out, key)?;
+        value = inner;
+    }
+
+    write!(out, " = ")?;
+    match value {
+        Value::Array(values) if pretty && values.len() > 1 => {
+            writeln!(out, "[")?;
+            for value in values {
+                write!(out, "    ")?;
+                write_value(out, value)?;
+                writeln!(out, ",")?;
+            }
+            write!(out, "]")
+        },
+        _ => write_value(out, value),
+    }
+}
+
+/// bare keys are written as is, anything else is quoted
+pub fn write_key(out: &mut impl Write, key: &Key) -> std::fmt::Result {
+    let name = key.name();
+
+    if !name.is_empty() && name.chars().all(|c| BARE_KEY_CHARS.contains(&c)) {
+        write!(out, "{name}")
+    } else {
+        write_string(out, name)
+    }
+}
+
+/// writes a basic string, escapes are looked up in `ESCAPE_SEQUENCE_TO_CHAR` and other control characters become `\uXXXX`
+pub fn write_string(out: &mut impl Write, value: &str) -> std::fmt::Result {
+    out.write_char(DOUBLE_QUOTE)?;
+
+    for c in value.chars() {
+        if let Some((sequence, _)) = ESCAPE_SEQUENCE_TO_CHAR.iter().find(|(_, escaped)| *escaped == c) {
+            write!(out, "{sequence}")?;
+        } else if c.is_special_control() {
+            write!(out, "\\u{:04X}", c as u32)?;
+        } else {
+            out.write_char(c)?;
+        }
+    }
+
+    out.write_char(DOUBLE_QUOTE)
+}
+
+/// floats always keep a decimal point or an exponent so they are not read back as integers
+pub fn write_number(out: &mut impl Write, value: &NumberType) -> std::fmt::Result {
+    match value {
+        NumberType::Integer(v) => write!(out, "{v}"),
+        NumberType::Float(v) if v.is_nan() => write!(out, "{}nan", if v.is_sign_negative() { "-" } else { "" }),
+        NumberType::Float(v) if v.is_infinite() => write!(out, "{}inf", if v.is_sign_negative() { "-" } else { "" }),
+        NumberType::Float(v) => write!(out, "{v:?}"),
+        #[cfg(feature = "big-integer")]
+        NumberType::BigInteger(v) => write!(out, "{v}"),
+    }
+}
+
+/// writes a value the way it appears after `=`, tables become inline tables
+pub fn write_value(out: &mut impl Write, value: &Value) -> std::fmt::Result {
+    match value {
+        Value::String(v) => write_string(out, v),
+        Value::Boolean(v) => write!(out, "{v}"),
+        Value::Number(v) => write_number(out, v),
+        Value::DateTime(v) => write!(out, "{v}"),
+        Value::Array(values) => {
+            write!(out, "[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ", ")?;
+                }
+                write_value(out, value)?;
+            }
+            write!(out, "]")
+        },
+        Value::Nested(table) => write_inline_table(out, table),
+        Value::ArrayOfTables(tables) => {
+            write!(out, "[")?;
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ", ")?;
+                }
+                write_inline_table(out, table)?;
+            }
+            write!(out, "]")
+        },
+    }
+}
+
+fn write_inline_table(out: &mut impl Write, table: &Table) -> std::fmt::Result {
+    if table.is_empty() {
+        return write!(out, "{{}}");
+    }
+
+    write!(out, "{{ ")?;
+    for (i, (key, value)) in sorted(table).into_iter().enumerate() {
+        if i > 0 {
+            write!(out, ", ")?;
+        }
+        write_key(out, key)?;
+        write!(out, " = ")?;
+        write_value(out, value)?;
+    }
+    write!(out, " }}")
+}